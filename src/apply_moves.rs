@@ -1,10 +1,36 @@
 use crate::constants::*;
 use crate::initialize_board::Board;
 use crate::pseudo_legal_move_generation::Move;
-use crate::utils::*;
+use crate::square::*;
+use crate::zobrist::ZOBRIST;
+
+/// Everything `make_move` destroys that `unmake_move` needs to restore the position
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UndoInfo {
+    pub captured: Option<PieceType>,
+    pub castling_rights: u8,
+    pub en_passant: Option<Square>,
+    pub half_moves: u32,
+    pub hash: u64,
+}
 
 impl Board {
+    /// Apply a move without keeping undo information
     pub fn apply_move(&mut self, m: &Move) {
+        self.make_move(m);
+    }
+
+    /// Apply a move and return the information needed to take it back with `unmake_move`
+    pub fn make_move(&mut self, m: &Move) -> UndoInfo {
+        debug_assert_eq!(*m, self.encode_move((*m).into()));
+        let mut undo = UndoInfo {
+            captured: None,
+            castling_rights: self.castling_rights,
+            en_passant: self.en_passant,
+            half_moves: self.half_moves,
+            hash: self.hash,
+        };
+
         let color = self.turn;
         let opp_color = if color == Color::White {
             Color::Black
@@ -12,145 +38,119 @@ impl Board {
             Color::White
         };
 
-        let moving_piece = m.moving_piece(&self);
+        let moving_piece = m.moving_piece(self).expect("no piece on the from square");
+        let (from, to) = (m.from(), m.to());
+        let keys = &*ZOBRIST;
 
-        // Clear 'from' square
-        clear_bit(
-            &mut self.pieces[color as usize][moving_piece.unwrap() as usize],
-            m.from,
-        );
+        // Remove the old castling rights and en passant file from the hash;
+        // the new ones are hashed back in once they are known
+        self.hash ^= keys.castling[self.castling_rights as usize];
+        if let Some(ep) = self.en_passant {
+            self.hash ^= keys.en_passant(ep);
+        }
 
-        clear_bit(&mut self.occupied, m.from);
+        // Clear 'from' square
+        self.remove_piece(color, moving_piece, from);
+        self.hash ^= keys.piece(color, moving_piece, from);
 
         // handle promotion
-        let placed_piece = if let Some(promo) = m.promotion {
-            promo
-        } else {
-            moving_piece.unwrap()
-        };
+        let placed_piece = m.promotion().unwrap_or(moving_piece);
 
         // handle capture (including en passant)
         let mut captured_piece: Option<PieceType> = None;
-        let captured_sq =
-            if moving_piece.unwrap() == PieceType::Pawn && self.en_passant == Some(m.to) {
-                // En passant capture happens on the square behind 'to'
-                let ep_capture_sq = if color == Color::White {
-                    m.to - 8
-                } else {
-                    m.to + 8
-                };
-                // Find opponent's pawn there
-                clear_bit(
-                    &mut self.pieces[opp_color as usize][PieceType::Pawn as usize],
-                    ep_capture_sq,
-                );
-                clear_bit(&mut self.occupied, ep_capture_sq);
-                captured_piece = Some(PieceType::Pawn);
-                ep_capture_sq
-            } else {
-                // Normal capture
-                for pt_idx in 0..6 {
-                    if get_bit(self.pieces[opp_color as usize][pt_idx], m.to) {
-                        captured_piece = PieceType::from_usize(pt_idx);
-                        clear_bit(&mut self.pieces[opp_color as usize][pt_idx], m.to);
-                        break;
-                    }
-                }
-                clear_bit(&mut self.occupied, m.to);
-                m.to
-            };
+        let captured_sq = if m.is_en_passant() {
+            // En passant capture happens on the square behind 'to',
+            // beside the square the pawn came from
+            let ep_capture_sq = Square::from_coords(to.file(), from.rank());
+            self.remove_piece(opp_color, PieceType::Pawn, ep_capture_sq);
+            self.hash ^= keys.piece(opp_color, PieceType::Pawn, ep_capture_sq);
+            captured_piece = Some(PieceType::Pawn);
+            ep_capture_sq
+        } else {
+            // Normal capture
+            if let Some(captured) = m.captured_piece(self) {
+                self.remove_piece(opp_color, captured, to);
+                self.hash ^= keys.piece(opp_color, captured, to);
+                captured_piece = Some(captured);
+            }
+            to
+        };
 
         // place piece on 'to' square
-        set_bit(
-            &mut self.pieces[color as usize][placed_piece as usize],
-            m.to,
-        );
-        set_bit(&mut self.occupied, m.to);
+        self.put_piece(color, placed_piece, to);
+        self.hash ^= keys.piece(color, placed_piece, to);
 
         // Special: Castling
-        if moving_piece.unwrap() == PieceType::King && (m.from as i8 - m.to as i8).abs() == 2 {
+        if m.is_castling() {
             // Determine rook move
-            let (rook_from, rook_to) = if m.to > m.from {
-                // king side
-                (m.from + 3, m.from + 1)
-            } else {
-                // queen side
-                (m.from - 4, m.from - 1)
-            };
+            let path = m
+                .castling_path()
+                .expect("castling flag on a non-castling move");
+            let (rook_from, rook_to) = (path.rook_from, path.rook_to);
 
-            clear_bit(
-                &mut self.pieces[color as usize][PieceType::Rook as usize],
-                rook_from,
-            );
-            set_bit(
-                &mut self.pieces[color as usize][PieceType::Rook as usize],
-                rook_to,
-            );
-            clear_bit(&mut self.occupied, rook_from);
-            set_bit(&mut self.occupied, rook_to);
+            self.remove_piece(color, PieceType::Rook, rook_from);
+            self.put_piece(color, PieceType::Rook, rook_to);
+            self.hash ^= keys.piece(color, PieceType::Rook, rook_from);
+            self.hash ^= keys.piece(color, PieceType::Rook, rook_to);
         }
 
         // Update castling rights
-        if moving_piece.unwrap() == PieceType::King {
+        if moving_piece == PieceType::King {
             // King moved -> lose both rights for this color
             if color == Color::White {
-                self.castling_rights &= !0b0011; // Clear white KQ
+                self.castling_rights &= !(WHITE_KINGSIDE | WHITE_QUEENSIDE);
             } else {
-                self.castling_rights &= !0b1100; // Clear black KQ
+                self.castling_rights &= !(BLACK_KINGSIDE | BLACK_QUEENSIDE);
             }
-        } else if moving_piece.unwrap() == PieceType::Rook {
+        } else if moving_piece == PieceType::Rook {
             // Rook moved from corner -> lose corressponding right
             if color == Color::White {
-                if m.from == 0 {
-                    // A1
-                    self.castling_rights &= !0b0010; // Clear white queenside
-                } else if m.from == 7 {
-                    // H1
-                    self.castling_rights &= !0b0001; // Clear white kingside
+                if from == A1 {
+                    self.castling_rights &= !WHITE_QUEENSIDE;
+                } else if from == H1 {
+                    self.castling_rights &= !WHITE_KINGSIDE;
                 }
             } else {
-                if m.from == 56 {
-                    // A8
-                    self.castling_rights &= !0b1000; // Clear black queenside
-                } else if m.from == 63 {
-                    // H8
-                    self.castling_rights &= !0b0100; // Clear black kingside
+                if from == A8 {
+                    self.castling_rights &= !BLACK_QUEENSIDE;
+                } else if from == H8 {
+                    self.castling_rights &= !BLACK_KINGSIDE;
                 }
             }
         }
 
         // if opponent rook was captured on corner, remove their castling right
-        if let Some(capt_pt) = captured_piece {
-            if capt_pt == PieceType::Rook {
-                if color == Color::White {
-                    if captured_sq == 56 {
-                        self.castling_rights &= !0b1000;
-                    } // Black queenside
-                    if captured_sq == 63 {
-                        self.castling_rights &= !0b0100;
-                    } // Black kingside
-                } else {
-                    if captured_sq == 0 {
-                        self.castling_rights &= !0b0010;
-                    } // White queenside
-                    if captured_sq == 7 {
-                        self.castling_rights &= !0b0001;
-                    } // White kingside
+        if captured_piece == Some(PieceType::Rook) {
+            if color == Color::White {
+                if captured_sq == A8 {
+                    self.castling_rights &= !BLACK_QUEENSIDE;
+                }
+                if captured_sq == H8 {
+                    self.castling_rights &= !BLACK_KINGSIDE;
+                }
+            } else {
+                if captured_sq == A1 {
+                    self.castling_rights &= !WHITE_QUEENSIDE;
+                }
+                if captured_sq == H1 {
+                    self.castling_rights &= !WHITE_KINGSIDE;
                 }
             }
         }
 
         // update en passant target
         self.en_passant = None;
-        if moving_piece.unwrap() == PieceType::Pawn && (m.from as i8 - m.to as i8).abs() == 16 {
-            // Double pawn push
-            let ep_sq = ((m.from as i8 + m.to as i8) / 2) as Square;
+        if m.is_double_push() {
+            // The square passed over: halfway between two squares on one file
+            let ep_sq = Square::from_index((from.to_u8() + to.to_u8()) / 2);
             self.en_passant = Some(ep_sq);
+            self.hash ^= keys.en_passant(ep_sq);
         }
+        self.hash ^= keys.castling[self.castling_rights as usize];
 
         // Update_counters
         //
-        if moving_piece.unwrap() == PieceType::Pawn || captured_piece.is_some() {
+        if moving_piece == PieceType::Pawn || captured_piece.is_some() {
             self.half_moves = 0;
         } else {
             self.half_moves += 1;
@@ -162,5 +162,86 @@ impl Board {
 
         // Flip turn
         self.turn = opp_color;
+        self.hash ^= keys.side;
+
+        debug_assert_eq!(
+            self.hash,
+            self.compute_hash(),
+            "incremental zobrist hash diverged from a full recompute"
+        );
+        debug_assert_eq!(
+            (self.psq, self.phase),
+            self.compute_psq(),
+            "incremental evaluation sums diverged from a full recompute"
+        );
+        debug_assert_eq!(
+            self.pawn_key,
+            self.compute_pawn_key(),
+            "incremental pawn key diverged from a full recompute"
+        );
+
+        undo.captured = captured_piece;
+        undo
+    }
+
+    /// Take back a move made with `make_move`, restoring the position exactly
+    pub fn unmake_move(&mut self, m: &Move, undo: &UndoInfo) {
+        // The side that made the move
+        let opp_color = self.turn;
+        let color = if opp_color == Color::White {
+            Color::Black
+        } else {
+            Color::White
+        };
+
+        let (from, to) = (m.from(), m.to());
+
+        // Whatever stands on 'to' now is the moved (or promoted) piece
+        let placed_piece = self
+            .piece_on(to)
+            .expect("no piece on the to square")
+            .piece_type;
+        let moving_piece = if m.is_promotion() {
+            PieceType::Pawn
+        } else {
+            placed_piece
+        };
+
+        // Move the piece back to 'from'
+        self.remove_piece(color, placed_piece, to);
+        self.put_piece(color, moving_piece, from);
+
+        // Put back the captured piece (behind 'to' for en passant)
+        if let Some(captured) = undo.captured {
+            let captured_sq = if m.is_en_passant() {
+                Square::from_coords(to.file(), from.rank())
+            } else {
+                to
+            };
+            self.put_piece(opp_color, captured, captured_sq);
+        }
+
+        // Move the castling rook back
+        if m.is_castling() {
+            let path = m
+                .castling_path()
+                .expect("castling flag on a non-castling move");
+            let (rook_from, rook_to) = (path.rook_from, path.rook_to);
+
+            self.remove_piece(color, PieceType::Rook, rook_to);
+            self.put_piece(color, PieceType::Rook, rook_from);
+        }
+
+        // Restore state
+        self.castling_rights = undo.castling_rights;
+        self.en_passant = undo.en_passant;
+        self.half_moves = undo.half_moves;
+        self.hash = undo.hash;
+
+        if color == Color::Black {
+            self.full_moves -= 1;
+        }
+
+        self.turn = color;
     }
 }
@@ -1,144 +1,197 @@
-use crate::constants::Bitboard;
+use std::sync::LazyLock;
+
+use crate::constants::*;
+use crate::magic::*;
+
 // Precomputed knight attacks for each square
 pub const KNIGHT_ATTACKS: [Bitboard; 64] = [
-    0x0000000000020400,
-    0x0000000000050800,
-    0x00000000000a1100,
-    0x0000000000142200,
-    0x0000000000284400,
-    0x0000000000508800,
-    0x0000000000a01000,
-    0x0000000000402000,
-    0x0000000002040004,
-    0x0000000005080008,
-    0x000000000a110011,
-    0x0000000014220022,
-    0x0000000028440044,
-    0x0000000050880088,
-    0x00000000a0100010,
-    0x0000000040200020,
-    0x0000000204000402,
-    0x0000000508000805,
-    0x0000000a1100110a,
-    0x0000001422002214,
-    0x0000002844004428,
-    0x0000005088008850,
-    0x000000a0100010a0,
-    0x0000004020002040,
-    0x0000020400040200,
-    0x0000050800080500,
-    0x00000a1100110a00,
-    0x0000142200221400,
-    0x0000284400442800,
-    0x0000508800885000,
-    0x0000a0100010a000,
-    0x0000402000204000,
-    0x0002040004020000,
-    0x0005080008050000,
-    0x000a1100110a0000,
-    0x0014220022140000,
-    0x0028440044280000,
-    0x0050880088500000,
-    0x00a0100010a00000,
-    0x0040200020400000,
-    0x0204000402000000,
-    0x0508000805000000,
-    0x0a1100110a000000,
-    0x1422002214000000,
-    0x2844004428000000,
-    0x5088008850000000,
-    0x0a0100010a000000,
-    0x4020002040000000,
-    0x0400040200000000,
-    0x0800080500000000,
-    0x1100110a00000000,
-    0x2200221400000000,
-    0x4400442800000000,
-    0x8800885000000000,
-    0x100010a000000000,
-    0x2000204000000000,
-    0x0004020000000000,
-    0x0008050000000000,
-    0x00110a0000000000,
-    0x0022140000000000,
-    0x0044280000000000,
-    0x0088500000000000,
-    0x0010a00000000000,
-    0x0020400000000000,
+    Bitboard(0x0000000000020400),
+    Bitboard(0x0000000000050800),
+    Bitboard(0x00000000000a1100),
+    Bitboard(0x0000000000142200),
+    Bitboard(0x0000000000284400),
+    Bitboard(0x0000000000508800),
+    Bitboard(0x0000000000a01000),
+    Bitboard(0x0000000000402000),
+    Bitboard(0x0000000002040004),
+    Bitboard(0x0000000005080008),
+    Bitboard(0x000000000a110011),
+    Bitboard(0x0000000014220022),
+    Bitboard(0x0000000028440044),
+    Bitboard(0x0000000050880088),
+    Bitboard(0x00000000a0100010),
+    Bitboard(0x0000000040200020),
+    Bitboard(0x0000000204000402),
+    Bitboard(0x0000000508000805),
+    Bitboard(0x0000000a1100110a),
+    Bitboard(0x0000001422002214),
+    Bitboard(0x0000002844004428),
+    Bitboard(0x0000005088008850),
+    Bitboard(0x000000a0100010a0),
+    Bitboard(0x0000004020002040),
+    Bitboard(0x0000020400040200),
+    Bitboard(0x0000050800080500),
+    Bitboard(0x00000a1100110a00),
+    Bitboard(0x0000142200221400),
+    Bitboard(0x0000284400442800),
+    Bitboard(0x0000508800885000),
+    Bitboard(0x0000a0100010a000),
+    Bitboard(0x0000402000204000),
+    Bitboard(0x0002040004020000),
+    Bitboard(0x0005080008050000),
+    Bitboard(0x000a1100110a0000),
+    Bitboard(0x0014220022140000),
+    Bitboard(0x0028440044280000),
+    Bitboard(0x0050880088500000),
+    Bitboard(0x00a0100010a00000),
+    Bitboard(0x0040200020400000),
+    Bitboard(0x0204000402000000),
+    Bitboard(0x0508000805000000),
+    Bitboard(0x0a1100110a000000),
+    Bitboard(0x1422002214000000),
+    Bitboard(0x2844004428000000),
+    Bitboard(0x5088008850000000),
+    Bitboard(0xa0100010a0000000),
+    Bitboard(0x4020002040000000),
+    Bitboard(0x0400040200000000),
+    Bitboard(0x0800080500000000),
+    Bitboard(0x1100110a00000000),
+    Bitboard(0x2200221400000000),
+    Bitboard(0x4400442800000000),
+    Bitboard(0x8800885000000000),
+    Bitboard(0x100010a000000000),
+    Bitboard(0x2000204000000000),
+    Bitboard(0x0004020000000000),
+    Bitboard(0x0008050000000000),
+    Bitboard(0x00110a0000000000),
+    Bitboard(0x0022140000000000),
+    Bitboard(0x0044280000000000),
+    Bitboard(0x0088500000000000),
+    Bitboard(0x0010a00000000000),
+    Bitboard(0x0020400000000000),
 ];
 
 // Precomputed king attacks
 pub const KING_ATTACKS: [Bitboard; 64] = [
     // Rank 1 (A1–H1)
-    0x0000000000000302,
-    0x0000000000000705,
-    0x0000000000000e0a,
-    0x0000000000001c14,
-    0x0000000000003828,
-    0x0000000000007050,
-    0x000000000000e0a0,
-    0x000000000000c040,
+    Bitboard(0x0000000000000302),
+    Bitboard(0x0000000000000705),
+    Bitboard(0x0000000000000e0a),
+    Bitboard(0x0000000000001c14),
+    Bitboard(0x0000000000003828),
+    Bitboard(0x0000000000007050),
+    Bitboard(0x000000000000e0a0),
+    Bitboard(0x000000000000c040),
     // Rank 2 (A2–H2)
-    0x0000000000030203,
-    0x0000000000070507,
-    0x00000000000e0a0e,
-    0x00000000001c141c,
-    0x0000000000382838,
-    0x0000000000705070,
-    0x0000000000e0a0e0,
-    0x0000000000c040c0,
+    Bitboard(0x0000000000030203),
+    Bitboard(0x0000000000070507),
+    Bitboard(0x00000000000e0a0e),
+    Bitboard(0x00000000001c141c),
+    Bitboard(0x0000000000382838),
+    Bitboard(0x0000000000705070),
+    Bitboard(0x0000000000e0a0e0),
+    Bitboard(0x0000000000c040c0),
     // Rank 3 (A3–H3)
-    0x0000000003020300,
-    0x0000000007050700,
-    0x000000000e0a0e00,
-    0x000000001c141c00,
-    0x0000000038283800,
-    0x0000000070507000,
-    0x00000000e0a0e000,
-    0x00000000c040c000,
+    Bitboard(0x0000000003020300),
+    Bitboard(0x0000000007050700),
+    Bitboard(0x000000000e0a0e00),
+    Bitboard(0x000000001c141c00),
+    Bitboard(0x0000000038283800),
+    Bitboard(0x0000000070507000),
+    Bitboard(0x00000000e0a0e000),
+    Bitboard(0x00000000c040c000),
     // Rank 4 (A4–H4)
-    0x0000000302030000,
-    0x0000000705070000,
-    0x0000000e0a0e0000,
-    0x0000001c141c0000,
-    0x0000003828380000,
-    0x0000007050700000,
-    0x000000e0a0e00000,
-    0x000000c040c00000,
+    Bitboard(0x0000000302030000),
+    Bitboard(0x0000000705070000),
+    Bitboard(0x0000000e0a0e0000),
+    Bitboard(0x0000001c141c0000),
+    Bitboard(0x0000003828380000),
+    Bitboard(0x0000007050700000),
+    Bitboard(0x000000e0a0e00000),
+    Bitboard(0x000000c040c00000),
     // Rank 5 (A5–H5)
-    0x0000030203000000,
-    0x0000070507000000,
-    0x00000e0a0e000000,
-    0x00001c141c000000,
-    0x0000382838000000,
-    0x0000705070000000,
-    0x0000e0a0e0000000,
-    0x0000c040c0000000,
+    Bitboard(0x0000030203000000),
+    Bitboard(0x0000070507000000),
+    Bitboard(0x00000e0a0e000000),
+    Bitboard(0x00001c141c000000),
+    Bitboard(0x0000382838000000),
+    Bitboard(0x0000705070000000),
+    Bitboard(0x0000e0a0e0000000),
+    Bitboard(0x0000c040c0000000),
     // Rank 6 (A6–H6)
-    0x0003020300000000,
-    0x0007050700000000,
-    0x000e0a0e00000000,
-    0x001c141c00000000,
-    0x0038283800000000,
-    0x0070507000000000,
-    0x00e0a0e000000000,
-    0x00c040c000000000,
+    Bitboard(0x0003020300000000),
+    Bitboard(0x0007050700000000),
+    Bitboard(0x000e0a0e00000000),
+    Bitboard(0x001c141c00000000),
+    Bitboard(0x0038283800000000),
+    Bitboard(0x0070507000000000),
+    Bitboard(0x00e0a0e000000000),
+    Bitboard(0x00c040c000000000),
     // Rank 7 (A7–H7)
-    0x0302030000000000,
-    0x0705070000000000,
-    0x0e0a0e0000000000,
-    0x1c141c0000000000,
-    0x3828380000000000,
-    0x7050700000000000,
-    0xe0a0e00000000000,
-    0xc040c00000000000,
+    Bitboard(0x0302030000000000),
+    Bitboard(0x0705070000000000),
+    Bitboard(0x0e0a0e0000000000),
+    Bitboard(0x1c141c0000000000),
+    Bitboard(0x3828380000000000),
+    Bitboard(0x7050700000000000),
+    Bitboard(0xe0a0e00000000000),
+    Bitboard(0xc040c00000000000),
     // Rank 8 (A8–H8)
-    0x0203000000000000,
-    0x0507000000000000,
-    0x0a0e000000000000,
-    0x141c000000000000,
-    0x2838000000000000,
-    0x5070000000000000,
-    0xa0e0000000000000,
-    0x40c0000000000000,
+    Bitboard(0x0203000000000000),
+    Bitboard(0x0507000000000000),
+    Bitboard(0x0a0e000000000000),
+    Bitboard(0x141c000000000000),
+    Bitboard(0x2838000000000000),
+    Bitboard(0x5070000000000000),
+    Bitboard(0xa0e0000000000000),
+    Bitboard(0x40c0000000000000),
 ];
+
+/// Squares strictly between two aligned squares, for every pair (empty if not aligned)
+static BETWEEN: LazyLock<Box<[[Bitboard; 64]; 64]>> = LazyLock::new(|| {
+    let mut table = Box::new([[Bitboard::EMPTY; 64]; 64]);
+    for a in Square::all() {
+        for b in Square::all() {
+            if rook_attacks_ray(a, Bitboard::EMPTY).contains(b) {
+                table[a.index()][b.index()] =
+                    rook_attacks_ray(a, b.bit()) & rook_attacks_ray(b, a.bit());
+            } else if bishop_attacks_ray(a, Bitboard::EMPTY).contains(b) {
+                table[a.index()][b.index()] =
+                    bishop_attacks_ray(a, b.bit()) & bishop_attacks_ray(b, a.bit());
+            }
+        }
+    }
+    table
+});
+
+/// Full board-edge-to-edge line through two aligned squares (empty if not aligned)
+static LINE: LazyLock<Box<[[Bitboard; 64]; 64]>> = LazyLock::new(|| {
+    let mut table = Box::new([[Bitboard::EMPTY; 64]; 64]);
+    for a in Square::all() {
+        for b in Square::all() {
+            let ends = a.bit() | b.bit();
+            if a != b && rook_attacks_ray(a, Bitboard::EMPTY).contains(b) {
+                table[a.index()][b.index()] = (rook_attacks_ray(a, Bitboard::EMPTY)
+                    & rook_attacks_ray(b, Bitboard::EMPTY))
+                    | ends;
+            } else if a != b && bishop_attacks_ray(a, Bitboard::EMPTY).contains(b) {
+                table[a.index()][b.index()] = (bishop_attacks_ray(a, Bitboard::EMPTY)
+                    & bishop_attacks_ray(b, Bitboard::EMPTY))
+                    | ends;
+            }
+        }
+    }
+    table
+});
+
+/// Squares strictly between `a` and `b` if they share a rank, file or diagonal
+#[inline(always)]
+pub fn between(a: Square, b: Square) -> Bitboard {
+    BETWEEN[a.index()][b.index()]
+}
+
+/// The whole line through `a` and `b` (including both) if they are aligned
+#[inline(always)]
+pub fn line(a: Square, b: Square) -> Bitboard {
+    LINE[a.index()][b.index()]
+}
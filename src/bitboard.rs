@@ -0,0 +1,242 @@
+use std::fmt;
+use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not, Shl, Shr};
+
+use crate::square::*;
+
+/// A set of squares, one bit per square (bit 0 = a1, bit 63 = h8).
+///
+/// The raw `u64` stays reachable through the public field for table
+/// building and hashing tricks.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Bitboard(pub u64);
+
+const NOT_A_FILE: u64 = 0xfefefefefefefefe;
+const NOT_H_FILE: u64 = 0x7f7f7f7f7f7f7f7f;
+
+impl Bitboard {
+    pub const EMPTY: Bitboard = Bitboard(0);
+    pub const FULL: Bitboard = Bitboard(!0);
+
+    pub const fn from_square(sq: Square) -> Bitboard {
+        Bitboard(1u64 << sq.to_u8())
+    }
+
+    /// Every square on the file
+    pub const fn file(file: File) -> Bitboard {
+        Bitboard(0x0101010101010101 << file.to_u8())
+    }
+
+    /// Every square on the rank
+    pub const fn rank(rank: Rank) -> Bitboard {
+        Bitboard(0xFF << (rank.to_u8() * 8))
+    }
+
+    /// The a1-h8 direction diagonal through `sq`
+    pub const fn diagonal(sq: Square) -> Bitboard {
+        const MAIN: u64 = 0x8040201008040201; // a1-h8
+        // Moving the main diagonal down one rank moves it one step to the right
+        let shift = sq.file().to_u8() as i32 - sq.rank().to_u8() as i32;
+        if shift >= 0 {
+            Bitboard(MAIN >> (shift * 8))
+        } else {
+            Bitboard(MAIN << (-shift * 8))
+        }
+    }
+
+    /// The a8-h1 direction diagonal through `sq`
+    pub const fn anti_diagonal(sq: Square) -> Bitboard {
+        const ANTI: u64 = 0x0102040810204080; // a8-h1
+        let shift = sq.file().to_u8() as i32 + sq.rank().to_u8() as i32 - 7;
+        if shift >= 0 {
+            Bitboard(ANTI << (shift * 8))
+        } else {
+            Bitboard(ANTI >> (-shift * 8))
+        }
+    }
+
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    pub const fn popcount(self) -> u32 {
+        self.0.count_ones()
+    }
+
+    /// Two or more squares set; cheaper than `popcount() > 1`
+    pub const fn more_than_one(self) -> bool {
+        self.0 & self.0.wrapping_sub(1) != 0
+    }
+
+    pub const fn contains(self, sq: Square) -> bool {
+        self.0 & (1u64 << sq.to_u8()) != 0
+    }
+
+    /// Lowest square in the set
+    pub const fn lsb(self) -> Option<Square> {
+        if self.0 == 0 {
+            None
+        } else {
+            Some(Square::from_index(self.0.trailing_zeros() as u8))
+        }
+    }
+
+    /// Remove and return the lowest square in the set
+    pub fn pop_lsb(&mut self) -> Option<Square> {
+        let sq = self.lsb()?;
+        self.0 &= self.0 - 1;
+        Some(sq)
+    }
+
+    // Shifts by one square; nothing wraps around a board edge
+
+    pub const fn north(self) -> Bitboard {
+        Bitboard(self.0 << 8)
+    }
+
+    pub const fn south(self) -> Bitboard {
+        Bitboard(self.0 >> 8)
+    }
+
+    pub const fn east(self) -> Bitboard {
+        Bitboard((self.0 << 1) & NOT_A_FILE)
+    }
+
+    pub const fn west(self) -> Bitboard {
+        Bitboard((self.0 >> 1) & NOT_H_FILE)
+    }
+
+    pub const fn north_east(self) -> Bitboard {
+        Bitboard((self.0 << 9) & NOT_A_FILE)
+    }
+
+    pub const fn north_west(self) -> Bitboard {
+        Bitboard((self.0 << 7) & NOT_H_FILE)
+    }
+
+    pub const fn south_east(self) -> Bitboard {
+        Bitboard((self.0 >> 7) & NOT_A_FILE)
+    }
+
+    pub const fn south_west(self) -> Bitboard {
+        Bitboard((self.0 >> 9) & NOT_H_FILE)
+    }
+}
+
+impl From<Square> for Bitboard {
+    fn from(sq: Square) -> Bitboard {
+        Bitboard::from_square(sq)
+    }
+}
+
+impl FromIterator<Square> for Bitboard {
+    fn from_iter<I: IntoIterator<Item = Square>>(iter: I) -> Self {
+        iter.into_iter()
+            .fold(Bitboard::EMPTY, |bb, sq| bb | Bitboard::from_square(sq))
+    }
+}
+
+/// Squares of a bitboard, lowest first
+pub struct Squares(Bitboard);
+
+impl Iterator for Squares {
+    type Item = Square;
+
+    #[inline(always)]
+    fn next(&mut self) -> Option<Square> {
+        self.0.pop_lsb()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let n = self.0.popcount() as usize;
+        (n, Some(n))
+    }
+}
+
+impl ExactSizeIterator for Squares {}
+
+impl IntoIterator for Bitboard {
+    type Item = Square;
+    type IntoIter = Squares;
+
+    fn into_iter(self) -> Squares {
+        Squares(self)
+    }
+}
+
+macro_rules! bit_op {
+    ($op:ident, $method:ident, $assign:ident, $assign_method:ident, $tok:tt) => {
+        impl $op for Bitboard {
+            type Output = Bitboard;
+
+            #[inline(always)]
+            fn $method(self, rhs: Bitboard) -> Bitboard {
+                Bitboard(self.0 $tok rhs.0)
+            }
+        }
+
+        impl $assign for Bitboard {
+            #[inline(always)]
+            fn $assign_method(&mut self, rhs: Bitboard) {
+                self.0 = self.0 $tok rhs.0;
+            }
+        }
+    };
+}
+
+bit_op!(BitAnd, bitand, BitAndAssign, bitand_assign, &);
+bit_op!(BitOr, bitor, BitOrAssign, bitor_assign, |);
+bit_op!(BitXor, bitxor, BitXorAssign, bitxor_assign, ^);
+
+impl Not for Bitboard {
+    type Output = Bitboard;
+
+    #[inline(always)]
+    fn not(self) -> Bitboard {
+        Bitboard(!self.0)
+    }
+}
+
+/// Raw shift: squares pushed past h-file wrap onto the next rank
+impl Shl<u32> for Bitboard {
+    type Output = Bitboard;
+
+    #[inline(always)]
+    fn shl(self, n: u32) -> Bitboard {
+        Bitboard(self.0 << n)
+    }
+}
+
+/// Raw shift: squares pushed past a-file wrap onto the previous rank
+impl Shr<u32> for Bitboard {
+    type Output = Bitboard;
+
+    #[inline(always)]
+    fn shr(self, n: u32) -> Bitboard {
+        Bitboard(self.0 >> n)
+    }
+}
+
+impl fmt::Debug for Bitboard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Bitboard({:#018x})", self.0)
+    }
+}
+
+/// An 8x8 grid, rank 8 on top, 'X' for set squares
+impl fmt::Display for Bitboard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for rank in (0..8).rev().filter_map(Rank::new) {
+            write!(f, "{} ", rank)?;
+            for file in (0..8).filter_map(File::new) {
+                let c = if self.contains(Square::from_coords(file, rank)) {
+                    'X'
+                } else {
+                    '.'
+                };
+                write!(f, " {}", c)?;
+            }
+            writeln!(f)?;
+        }
+        write!(f, "   a b c d e f g h")
+    }
+}
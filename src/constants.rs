@@ -1,8 +1,19 @@
+use crate::square::*;
+
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum Color {
     White,
     Black,
 }
+impl Color {
+    /// The other side
+    pub fn opposite(self) -> Self {
+        match self {
+            Color::White => Color::Black,
+            Color::Black => Color::White,
+        }
+    }
+}
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum PieceType {
@@ -32,33 +43,135 @@ impl PieceType {
     pub fn to_usize(self) -> usize {
         self as usize
     }
+
+    /// Uppercase letter as used in SAN ('P' for pawns)
+    pub fn to_char(self) -> char {
+        match self {
+            PieceType::Pawn => 'P',
+            PieceType::Knight => 'N',
+            PieceType::Bishop => 'B',
+            PieceType::Rook => 'R',
+            PieceType::Queen => 'Q',
+            PieceType::King => 'K',
+        }
+    }
+
+    /// Parse a piece letter, either case
+    pub fn from_char(c: char) -> Option<Self> {
+        match c.to_ascii_uppercase() {
+            'P' => Some(PieceType::Pawn),
+            'N' => Some(PieceType::Knight),
+            'B' => Some(PieceType::Bishop),
+            'R' => Some(PieceType::Rook),
+            'Q' => Some(PieceType::Queen),
+            'K' => Some(PieceType::King),
+            _ => None,
+        }
+    }
 }
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub struct Piece {
     pub color: Color,
     pub piece_type: PieceType,
 }
+impl Piece {
+    /// FEN letter: uppercase for White, lowercase for Black
+    pub fn to_char(self) -> char {
+        let c = self.piece_type.to_char();
+        if self.color == Color::White {
+            c
+        } else {
+            c.to_ascii_lowercase()
+        }
+    }
+}
+
+// Castling rights bit flags (see Board::castling_rights)
+pub const WHITE_KINGSIDE: u8 = 0b0001;
+pub const WHITE_QUEENSIDE: u8 = 0b0010;
+pub const BLACK_KINGSIDE: u8 = 0b0100;
+pub const BLACK_QUEENSIDE: u8 = 0b1000;
+
+/// Everything needed to castle one way
+pub struct CastlingPath {
+    pub right: u8,
+    pub king_from: Square,
+    pub king_to: Square,
+    pub rook_from: Square,
+    pub rook_to: Square,
+    /// Squares between king and rook, all of which must be empty
+    pub empty: Bitboard,
+    /// Squares the king crosses or lands on, none of which may be attacked
+    pub safe: Bitboard,
+}
+
+/// Castling paths indexed by [color][kingside = 0, queenside = 1]
+pub const CASTLING_PATHS: [[CastlingPath; 2]; 2] = [
+    [
+        CastlingPath {
+            right: WHITE_KINGSIDE,
+            king_from: E1,
+            king_to: G1,
+            rook_from: H1,
+            rook_to: F1,
+            empty: Bitboard(0x60), // F1 G1
+            safe: Bitboard(0x60),  // F1 G1
+        },
+        CastlingPath {
+            right: WHITE_QUEENSIDE,
+            king_from: E1,
+            king_to: C1,
+            rook_from: A1,
+            rook_to: D1,
+            empty: Bitboard(0x0E), // B1 C1 D1
+            safe: Bitboard(0x0C),  // C1 D1
+        },
+    ],
+    [
+        CastlingPath {
+            right: BLACK_KINGSIDE,
+            king_from: E8,
+            king_to: G8,
+            rook_from: H8,
+            rook_to: F8,
+            empty: Bitboard(0x60 << 56), // F8 G8
+            safe: Bitboard(0x60 << 56),  // F8 G8
+        },
+        CastlingPath {
+            right: BLACK_QUEENSIDE,
+            king_from: E8,
+            king_to: C8,
+            rook_from: A8,
+            rook_to: D8,
+            empty: Bitboard(0x0E << 56), // B8 C8 D8
+            safe: Bitboard(0x0C << 56),  // C8 D8
+        },
+    ],
+];
+
+// Square representation (0-63), see square.rs
+pub use crate::square::Square;
 
-// Square representation (0-63)
-pub type Square = u8;
+// Bitboard type, see bitboard.rs
+pub use crate::bitboard::Bitboard;
 
-// Bitboard type
-pub type Bitboard = u64;
+pub const RANK_1: Bitboard = Bitboard(0x00000000000000FF);
+pub const RANK_2: Bitboard = Bitboard(0x000000000000FF00);
+pub const RANK_3: Bitboard = Bitboard(0x0000000000FF0000);
+pub const RANK_4: Bitboard = Bitboard(0x00000000FF000000);
+pub const RANK_5: Bitboard = Bitboard(0x000000FF00000000);
+pub const RANK_6: Bitboard = Bitboard(0x0000FF0000000000);
+pub const RANK_7: Bitboard = Bitboard(0x00FF000000000000);
+pub const RANK_8: Bitboard = Bitboard(0xFF00000000000000);
 
-pub const RANK_1: Bitboard = 0x00000000000000FF;
-pub const RANK_2: Bitboard = 0x000000000000FF00;
-pub const RANK_3: Bitboard = 0x0000000000FF0000;
-pub const RANK_4: Bitboard = 0x00000000FF000000;
-pub const RANK_5: Bitboard = 0x000000FF00000000;
-pub const RANK_6: Bitboard = 0x0000FF0000000000;
-pub const RANK_7: Bitboard = 0x00FF000000000000;
-pub const RANK_8: Bitboard = 0xFF00000000000000;
+pub const FILE_A: Bitboard = Bitboard(0x0101010101010101);
+pub const FILE_B: Bitboard = Bitboard(0x0202020202020202);
+pub const FILE_C: Bitboard = Bitboard(0x0404040404040404);
+pub const FILE_D: Bitboard = Bitboard(0x0808080808080808);
+pub const FILE_E: Bitboard = Bitboard(0x1010101010101010);
+pub const FILE_F: Bitboard = Bitboard(0x2020202020202020);
+pub const FILE_G: Bitboard = Bitboard(0x4040404040404040);
+pub const FILE_H: Bitboard = Bitboard(0x8080808080808080);
 
-pub const FILE_A: Bitboard = 0x0101010101010101;
-pub const FILE_B: Bitboard = 0x0202020202020202;
-pub const FILE_C: Bitboard = 0x0404040404040404;
-pub const FILE_D: Bitboard = 0x0808080808080808;
-pub const FILE_E: Bitboard = 0x1010101010101010;
-pub const FILE_F: Bitboard = 0x2020202020202020;
-pub const FILE_G: Bitboard = 0x4040404040404040;
-pub const FILE_H: Bitboard = 0x8080808080808080;
+pub const LIGHT_SQUARES: Bitboard = Bitboard(0x55AA55AA55AA55AA);
+pub const DARK_SQUARES: Bitboard = Bitboard(!LIGHT_SQUARES.0);
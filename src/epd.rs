@@ -0,0 +1,351 @@
+use std::error::Error;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use crate::fen::{FenError, FenErrorKind, FenField, split_fields};
+use crate::initialize_board::Board;
+use crate::pseudo_legal_move_generation::Move;
+use crate::san::SanError;
+
+/// One operation of an EPD record, such as `bm Qg6;` or `id "WAC.001";`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EpdOperation {
+    pub opcode: String,
+    /// Operands as written, with the quotes around string operands removed
+    pub operands: Vec<String>,
+}
+
+/// A position from an EPD file: the first four FEN fields followed by operations.
+///
+/// The operations the engine acts on are also decoded: `bm` and `am` into
+/// moves, `dm` into a mate distance, and `hmvc`/`fmvn` into the board's counters.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Epd {
+    pub board: Board,
+    /// Every operation, in the order written
+    pub operations: Vec<EpdOperation>,
+    /// `bm`: the moves that solve the position
+    pub best_moves: Vec<Move>,
+    /// `am`: the moves that fail it
+    pub avoid_moves: Vec<Move>,
+    /// `dm`: mate in this many moves
+    pub direct_mate: Option<u32>,
+}
+
+impl Epd {
+    /// Parse a single EPD record
+    pub fn parse(epd: &str) -> Result<Epd, EpdError> {
+        Epd::parse_line(epd, 1)
+    }
+
+    /// Parse every record of an EPD file, skipping blank lines
+    pub fn parse_all(text: &str) -> Result<Vec<Epd>, EpdError> {
+        text.lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(i, line)| Epd::parse_line(line, i + 1))
+            .collect()
+    }
+
+    fn parse_line(epd: &str, line: usize) -> Result<Epd, EpdError> {
+        let error = |position, kind| EpdError {
+            line,
+            position,
+            kind,
+        };
+
+        // The position, read exactly as `Board::from_fen` reads the same fields
+        let fields = split_fields(epd);
+        if fields.len() < 4 {
+            let position = fields.last().map_or(0, |(start, f)| start + f.len());
+            let field = [
+                FenField::Placement,
+                FenField::SideToMove,
+                FenField::Castling,
+                FenField::EnPassant,
+            ][fields.len()];
+            let fen_error = FenError::new(field, position, FenErrorKind::MissingField);
+            return Err(error(position, EpdErrorKind::InvalidPosition(fen_error)));
+        }
+        let fields = &fields[..4];
+        let invalid = |e: FenError| error(e.position, EpdErrorKind::InvalidPosition(e));
+        let mut board = Board::parse_fen_prefix(fields, true).map_err(invalid)?;
+        board.validate(fields).map_err(invalid)?;
+        board.hash = board.compute_hash();
+
+        let (start, last) = fields[3];
+        let offset = start + last.len();
+        let operations = read_operations(&epd[offset..])
+            .map_err(|(position, kind)| error(offset + position, kind))?;
+
+        let mut best_moves = Vec::new();
+        let mut avoid_moves = Vec::new();
+        let mut direct_mate = None;
+        for (position, op) in &operations {
+            let number = || {
+                match op.operands.as_slice() {
+                    [n] => n.parse::<u32>().ok(),
+                    _ => None,
+                }
+                .ok_or_else(|| {
+                    error(
+                        offset + position,
+                        EpdErrorKind::InvalidOperand(op.opcode.clone()),
+                    )
+                })
+            };
+            match op.opcode.as_str() {
+                "bm" | "am" => {
+                    let moves = op
+                        .operands
+                        .iter()
+                        .map(|san| board.parse_san(san))
+                        .collect::<Result<Vec<Move>, SanError>>()
+                        .map_err(|e| error(offset + position, EpdErrorKind::InvalidMove(e)))?;
+                    if op.opcode == "bm" {
+                        best_moves.extend(moves);
+                    } else {
+                        avoid_moves.extend(moves);
+                    }
+                }
+                "dm" => direct_mate = Some(number()?),
+                "hmvc" => board.half_moves = number()?,
+                "fmvn" => board.full_moves = number()?.max(1),
+                _ => {}
+            }
+        }
+
+        Ok(Epd {
+            board,
+            operations: operations.into_iter().map(|(_, op)| op).collect(),
+            best_moves,
+            avoid_moves,
+            direct_mate,
+        })
+    }
+
+    /// Operands of the first operation with this opcode
+    pub fn operation(&self, opcode: &str) -> Option<&[String]> {
+        self.operations
+            .iter()
+            .find(|op| op.opcode == opcode)
+            .map(|op| op.operands.as_slice())
+    }
+
+    /// The `id` operation, naming the position within its suite
+    pub fn id(&self) -> Option<&str> {
+        self.operation("id")?.first().map(String::as_str)
+    }
+
+    /// One of the comment operations `c0` to `c9`
+    pub fn comment(&self, index: u8) -> Option<&str> {
+        self.operation(&format!("c{}", index))?
+            .first()
+            .map(String::as_str)
+    }
+
+    /// Whether playing `m` solves the position: it must be one of the `bm`
+    /// moves and none of the `am` moves. `None` when the record has neither.
+    pub fn is_solved_by(&self, m: Option<Move>) -> Option<bool> {
+        if self.best_moves.is_empty() && self.avoid_moves.is_empty() {
+            return None;
+        }
+        let Some(m) = m else {
+            return Some(false);
+        };
+        Some(
+            (self.best_moves.is_empty() || self.best_moves.contains(&m))
+                && !self.avoid_moves.contains(&m),
+        )
+    }
+}
+
+/// Split the text after the position into operations, each with its offset.
+/// Every operation ends with ';', except that the last may end the line instead.
+fn read_operations(text: &str) -> Result<Vec<(usize, EpdOperation)>, (usize, EpdErrorKind)> {
+    let is_word = |&(_, c): &(usize, char)| !c.is_ascii_whitespace() && c != ';';
+    let mut chars = text.char_indices().peekable();
+    let mut operations = Vec::new();
+    loop {
+        while chars.next_if(|(_, c)| c.is_ascii_whitespace()).is_some() {}
+        let Some(&(start, _)) = chars.peek() else {
+            return Ok(operations);
+        };
+
+        let mut opcode = String::new();
+        while let Some((_, c)) = chars.next_if(is_word) {
+            opcode.push(c);
+        }
+        if !opcode.starts_with(|c: char| c.is_ascii_alphabetic())
+            || !opcode
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_')
+        {
+            return Err((start, EpdErrorKind::InvalidOpcode(opcode)));
+        }
+
+        let mut operands = Vec::new();
+        loop {
+            while chars.next_if(|(_, c)| c.is_ascii_whitespace()).is_some() {}
+            match chars.next() {
+                None | Some((_, ';')) => break,
+                Some((quote, '"')) => {
+                    let mut operand = String::new();
+                    loop {
+                        match chars.next() {
+                            Some((_, '"')) => break,
+                            Some((_, c)) => operand.push(c),
+                            None => return Err((quote, EpdErrorKind::Unterminated)),
+                        }
+                    }
+                    operands.push(operand);
+                }
+                Some((_, c)) => {
+                    let mut operand = c.to_string();
+                    while let Some((_, c)) = chars.next_if(is_word) {
+                        operand.push(c);
+                    }
+                    operands.push(operand);
+                }
+            }
+        }
+        operations.push((start, EpdOperation { opcode, operands }));
+    }
+}
+
+/// What went wrong while reading EPD
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EpdErrorKind {
+    /// The four position fields do not make a valid position
+    InvalidPosition(FenError),
+    /// An opcode that does not start with a letter, or holds other than letters,
+    /// digits and '_'
+    InvalidOpcode(String),
+    /// A quoted operand runs to the end of the line
+    Unterminated,
+    /// A `bm` or `am` operand that is not legal SAN in the position
+    InvalidMove(SanError),
+    /// `dm`, `hmvc` or `fmvn` without exactly one number
+    InvalidOperand(String),
+}
+
+/// EPD parse error, pointing at the line (starting at 1) and the byte offset within it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EpdError {
+    pub line: usize,
+    pub position: usize,
+    pub kind: EpdErrorKind,
+}
+
+impl fmt::Display for EpdError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid EPD at line {}, position {}: ",
+            self.line, self.position
+        )?;
+        match &self.kind {
+            EpdErrorKind::InvalidPosition(e) => write!(f, "{}", e),
+            EpdErrorKind::InvalidOpcode(op) => write!(f, "invalid opcode '{}'", op),
+            EpdErrorKind::Unterminated => write!(f, "string not closed"),
+            EpdErrorKind::InvalidMove(e) => write!(f, "{}", e),
+            EpdErrorKind::InvalidOperand(op) => write!(f, "'{}' expects one number", op),
+        }
+    }
+}
+
+impl Error for EpdError {}
+
+/// How long a search may spend on each position of a suite
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchLimit {
+    Depth(u32),
+    Time(Duration),
+}
+
+/// The outcome of one suite position
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EpdResult {
+    /// The `id` operation, or the position's FEN when there is none
+    pub id: String,
+    /// The move the search returned
+    pub chosen: Option<Move>,
+    /// See `Epd::is_solved_by`; `None` for positions that are not scored
+    pub solved: Option<bool>,
+    pub elapsed: Duration,
+}
+
+/// Score and timing of a test suite, written one line per suite by `Display`
+/// followed by a line per failed position
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SuiteReport {
+    pub name: String,
+    pub results: Vec<EpdResult>,
+}
+
+impl SuiteReport {
+    /// Positions solved
+    pub fn solved(&self) -> usize {
+        self.results
+            .iter()
+            .filter(|r| r.solved == Some(true))
+            .count()
+    }
+
+    /// Positions with a `bm` or `am` to be scored against
+    pub fn scored(&self) -> usize {
+        self.results.iter().filter(|r| r.solved.is_some()).count()
+    }
+
+    /// Total search time over the suite
+    pub fn elapsed(&self) -> Duration {
+        self.results.iter().map(|r| r.elapsed).sum()
+    }
+}
+
+impl fmt::Display for SuiteReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {}/{} solved in {:.3}s",
+            self.name,
+            self.solved(),
+            self.scored(),
+            self.elapsed().as_secs_f64()
+        )?;
+        for result in self.results.iter().filter(|r| r.solved == Some(false)) {
+            let chosen = result.chosen.map_or("none".to_string(), |m| m.to_uci());
+            write!(f, "\n  failed {}: played {}", result.id, chosen)?;
+        }
+        Ok(())
+    }
+}
+
+/// Run `search` on every position of a suite and score its moves against
+/// `bm` and `am`. The search is handed the limit and is expected to keep to it;
+/// the time it actually took is measured here.
+pub fn run_suite(
+    name: &str,
+    positions: &[Epd],
+    limit: SearchLimit,
+    mut search: impl FnMut(&Board, SearchLimit) -> Option<Move>,
+) -> SuiteReport {
+    let results = positions
+        .iter()
+        .map(|epd| {
+            let started = Instant::now();
+            let chosen = search(&epd.board, limit);
+            let elapsed = started.elapsed();
+            EpdResult {
+                id: epd.id().map_or_else(|| epd.board.to_fen(), str::to_string),
+                chosen,
+                solved: epd.is_solved_by(chosen),
+                elapsed,
+            }
+        })
+        .collect();
+    SuiteReport {
+        name: name.to_string(),
+        results,
+    }
+}
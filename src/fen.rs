@@ -0,0 +1,563 @@
+use std::error::Error;
+use std::fmt;
+
+use crate::constants::*;
+use crate::initialize_board::Board;
+use crate::pseudo_legal_move_generation::square_to_algebraic;
+use crate::square::*;
+use crate::utils::*;
+
+/// FEN formatter for a board, created with `Board::fen`
+#[derive(Clone, Copy)]
+pub struct Fen<'a> {
+    board: &'a Board,
+    legal_en_passant_only: bool,
+}
+
+impl Fen<'_> {
+    /// Write the en passant square only when a legal en passant capture exists,
+    /// so that identical positions always produce identical FENs
+    pub fn legal_en_passant_only(mut self, yes: bool) -> Self {
+        self.legal_en_passant_only = yes;
+        self
+    }
+
+    fn en_passant(&self) -> Option<Square> {
+        if self.legal_en_passant_only {
+            self.board.legal_en_passant()
+        } else {
+            self.board.en_passant
+        }
+    }
+}
+
+impl fmt::Display for Fen<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let board = self.board;
+
+        // Piece placement, rank 8 first
+        for rank in (0..8).rev().filter_map(Rank::new) {
+            let mut empty = 0;
+            for file in (0..8).filter_map(File::new) {
+                match board.piece_on(Square::from_coords(file, rank)) {
+                    Some(piece) => {
+                        if empty > 0 {
+                            write!(f, "{}", empty)?;
+                            empty = 0;
+                        }
+                        write!(f, "{}", piece.to_char())?;
+                    }
+                    None => empty += 1,
+                }
+            }
+            if empty > 0 {
+                write!(f, "{}", empty)?;
+            }
+            if rank != Rank::ONE {
+                write!(f, "/")?;
+            }
+        }
+
+        // Side to move
+        let turn = if board.turn == Color::White { 'w' } else { 'b' };
+        write!(f, " {} ", turn)?;
+
+        // Castling rights
+        if board.castling_rights == 0 {
+            write!(f, "-")?;
+        } else {
+            for (flag, c) in [
+                (WHITE_KINGSIDE, 'K'),
+                (WHITE_QUEENSIDE, 'Q'),
+                (BLACK_KINGSIDE, 'k'),
+                (BLACK_QUEENSIDE, 'q'),
+            ] {
+                if board.castling_rights & flag != 0 {
+                    write!(f, "{}", c)?;
+                }
+            }
+        }
+
+        // En passant
+        match self.en_passant() {
+            Some(ep) => write!(f, " {}", square_to_algebraic(ep))?,
+            None => write!(f, " -")?,
+        }
+
+        // Counters
+        write!(f, " {} {}", board.half_moves, board.full_moves)
+    }
+}
+
+impl Board {
+    /// FEN formatter for this position; see `Fen` for options
+    pub fn fen(&self) -> Fen<'_> {
+        Fen {
+            board: self,
+            legal_en_passant_only: false,
+        }
+    }
+
+    /// Export the position as a FEN string, writing the en passant square as stored
+    pub fn to_fen(&self) -> String {
+        self.fen().to_string()
+    }
+}
+
+/// The six space-separated fields of a FEN string
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FenField {
+    Placement,
+    SideToMove,
+    Castling,
+    EnPassant,
+    HalfMoves,
+    FullMoves,
+}
+
+/// What went wrong while reading a FEN
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FenErrorKind {
+    /// A required field is absent
+    MissingField,
+    /// More than six fields
+    TooManyFields,
+    /// A character that is not allowed in this field
+    InvalidCharacter(char),
+    /// The placement does not have exactly 8 ranks
+    WrongRankCount(usize),
+    /// A rank does not add up to exactly 8 files
+    WrongFileCount,
+    /// A castling letter appears twice
+    DuplicateCastling(char),
+    /// Not a square, or not on the en passant rank for the side to move
+    InvalidSquare,
+    /// Not a non-negative number (or 0 for the fullmove counter)
+    InvalidCounter,
+    /// Not exactly one king of this color
+    KingCount(Color, u32),
+    /// A pawn on the first or last rank
+    PawnOnBackRank(Square),
+    /// The side that just moved is still in check
+    OpponentInCheck,
+    /// A castling right without the king and rook on their home squares
+    CastlingWithoutKingOrRook(char),
+    /// No pawn could just have double-pushed past the en passant square
+    ImpossibleEnPassant(Square),
+}
+
+/// FEN parse error, pointing at the field and the character position (byte offset) of the problem
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FenError {
+    pub field: FenField,
+    pub position: usize,
+    pub kind: FenErrorKind,
+}
+
+impl fmt::Display for FenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid FEN {:?} field at {}: ",
+            self.field, self.position
+        )?;
+        match self.kind {
+            FenErrorKind::MissingField => write!(f, "missing field"),
+            FenErrorKind::TooManyFields => write!(f, "too many fields"),
+            FenErrorKind::InvalidCharacter(c) => write!(f, "unexpected character '{}'", c),
+            FenErrorKind::WrongRankCount(n) => write!(f, "expected 8 ranks, found {}", n),
+            FenErrorKind::WrongFileCount => write!(f, "rank does not sum to 8 files"),
+            FenErrorKind::DuplicateCastling(c) => write!(f, "castling right '{}' repeated", c),
+            FenErrorKind::InvalidSquare => write!(f, "invalid en passant square"),
+            FenErrorKind::InvalidCounter => write!(f, "invalid move counter"),
+            FenErrorKind::KingCount(color, n) => {
+                write!(f, "{:?} has {} kings, expected exactly 1", color, n)
+            }
+            FenErrorKind::PawnOnBackRank(sq) => {
+                write!(f, "pawn on back rank at {}", square_to_algebraic(sq))
+            }
+            FenErrorKind::OpponentInCheck => write!(f, "side not to move is in check"),
+            FenErrorKind::CastlingWithoutKingOrRook(c) => {
+                write!(f, "castling right '{}' without king and rook at home", c)
+            }
+            FenErrorKind::ImpossibleEnPassant(sq) => write!(
+                f,
+                "en passant square {} does not fit the pawns",
+                square_to_algebraic(sq)
+            ),
+        }
+    }
+}
+
+impl Error for FenError {}
+
+impl FenError {
+    pub(crate) fn new(field: FenField, position: usize, kind: FenErrorKind) -> Self {
+        FenError {
+            field,
+            position,
+            kind,
+        }
+    }
+}
+
+/// Split on whitespace, keeping each field's byte offset
+pub(crate) fn split_fields(fen: &str) -> Vec<(usize, &str)> {
+    let mut fields = Vec::new();
+    let mut start = None;
+    for (i, c) in fen.char_indices() {
+        match (c.is_ascii_whitespace(), start) {
+            (true, Some(s)) => {
+                fields.push((s, &fen[s..i]));
+                start = None;
+            }
+            (false, None) => start = Some(i),
+            _ => {}
+        }
+    }
+    if let Some(s) = start {
+        fields.push((s, &fen[s..]));
+    }
+    fields
+}
+
+fn piece_from_char(c: char) -> Option<(Color, PieceType)> {
+    Some(match c {
+        'P' => (Color::White, PieceType::Pawn),
+        'N' => (Color::White, PieceType::Knight),
+        'B' => (Color::White, PieceType::Bishop),
+        'R' => (Color::White, PieceType::Rook),
+        'Q' => (Color::White, PieceType::Queen),
+        'K' => (Color::White, PieceType::King),
+        'p' => (Color::Black, PieceType::Pawn),
+        'n' => (Color::Black, PieceType::Knight),
+        'b' => (Color::Black, PieceType::Bishop),
+        'r' => (Color::Black, PieceType::Rook),
+        'q' => (Color::Black, PieceType::Queen),
+        'k' => (Color::Black, PieceType::King),
+        _ => return None,
+    })
+}
+
+/// The rank an en passant square must be on with `turn` to move, and the
+/// direction from it to the pawn that just double-pushed past it
+fn en_passant_rank(turn: Color) -> (Rank, i8) {
+    // White to move: black just pushed from rank 7 to rank 5, passing rank 6
+    match turn {
+        Color::White => (Rank::SIX, -1),
+        Color::Black => (Rank::THREE, 1),
+    }
+}
+
+impl Board {
+    /// Create a board from a FEN string, rejecting malformed fields and impossible positions.
+    /// Full FEN example: "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1"
+    ///                     <placement> <turn> <castling_rights> <en_passant> <half_moves> <full_moves>
+    /// The two counters may be left out and default to "0 1".
+    pub fn from_fen(fen: &str) -> Result<Self, FenError> {
+        let fields = split_fields(fen);
+        if fields.len() < 4 {
+            let position = fields.last().map_or(0, |(start, f)| start + f.len());
+            let field = [
+                FenField::Placement,
+                FenField::SideToMove,
+                FenField::Castling,
+                FenField::EnPassant,
+            ][fields.len()];
+            return Err(FenError::new(field, position, FenErrorKind::MissingField));
+        }
+        if fields.len() > 6 {
+            return Err(FenError::new(
+                FenField::FullMoves,
+                fields[6].0,
+                FenErrorKind::TooManyFields,
+            ));
+        }
+
+        let mut board = Board::parse_fen_prefix(&fields, true)?;
+
+        for (idx, field) in [(4, FenField::HalfMoves), (5, FenField::FullMoves)] {
+            let Some(&(start, text)) = fields.get(idx) else {
+                continue;
+            };
+            let value = match text.parse::<u32>() {
+                Ok(v) if field == FenField::HalfMoves || v > 0 => v,
+                _ => {
+                    return Err(FenError::new(field, start, FenErrorKind::InvalidCounter));
+                }
+            };
+            if field == FenField::HalfMoves {
+                board.half_moves = value;
+            } else {
+                board.full_moves = value;
+            }
+        }
+
+        board.validate(&fields)?;
+        board.hash = board.compute_hash();
+        Ok(board)
+    }
+
+    /// Create a board from a FEN string the forgiving way: only the piece placement is
+    /// required, unknown castling letters are ignored, bad counters fall back to "0 1",
+    /// and an en passant square with no pawn that could just have passed it is dropped.
+    /// The position is not checked for legality beyond what move generation relies on:
+    /// exactly one king per side, and an en passant square on the right rank.
+    pub fn from_fen_lenient(fen: &str) -> Result<Self, FenError> {
+        let fields = split_fields(fen);
+        if fields.is_empty() {
+            return Err(FenError::new(
+                FenField::Placement,
+                0,
+                FenErrorKind::MissingField,
+            ));
+        }
+
+        let mut board = Board::parse_fen_prefix(&fields, false)?;
+        board.validate_kings(fields[0].0)?;
+
+        if let Some(ep) = board.en_passant {
+            let (_, dy) = en_passant_rank(board.turn);
+            let opp_pawns = board.pieces[board.turn.opposite() as usize][PieceType::Pawn as usize];
+            let pushed_sq = ep.offset(0, dy).expect("en passant rank checked");
+            if !get_bit(opp_pawns, pushed_sq) {
+                board.en_passant = None;
+            }
+        }
+
+        if let Some((_, text)) = fields.get(4) {
+            board.half_moves = text.parse().unwrap_or(0);
+        }
+        if let Some((_, text)) = fields.get(5) {
+            board.full_moves = text.parse().unwrap_or(1);
+        }
+
+        board.hash = board.compute_hash();
+        Ok(board)
+    }
+
+    /// Parse placement, side to move, castling and en passant from the given fields.
+    /// Fields after the fourth are ignored; missing ones keep their defaults.
+    pub(crate) fn parse_fen_prefix(
+        fields: &[(usize, &str)],
+        strict: bool,
+    ) -> Result<Self, FenError> {
+        // The hash is computed once the position is parsed
+        let mut board = Board::empty();
+
+        // Piece placement
+        let (start, placement) = fields[0];
+        let rows: Vec<&str> = placement.split('/').collect();
+        if rows.len() != 8 {
+            return Err(FenError::new(
+                FenField::Placement,
+                start,
+                FenErrorKind::WrongRankCount(rows.len()),
+            ));
+        }
+
+        let mut position = start;
+        for (rank_idx, row) in rows.iter().enumerate() {
+            let rank = 7 - rank_idx as u8; // FEN starts from rank 8
+            let mut file = 0;
+
+            for c in row.chars() {
+                if let Some(num) = c.to_digit(10).filter(|n| (1..=8).contains(n)) {
+                    file += num as u8;
+                } else if let Some((color, piece_type)) = piece_from_char(c) {
+                    if file > 7 {
+                        return Err(FenError::new(
+                            FenField::Placement,
+                            position,
+                            FenErrorKind::WrongFileCount,
+                        ));
+                    }
+                    let sq = Square::from_index(rank * 8 + file);
+                    board.put_piece(color, piece_type, sq);
+
+                    file += 1;
+                } else {
+                    return Err(FenError::new(
+                        FenField::Placement,
+                        position,
+                        FenErrorKind::InvalidCharacter(c),
+                    ));
+                }
+
+                if file > 8 {
+                    return Err(FenError::new(
+                        FenField::Placement,
+                        position,
+                        FenErrorKind::WrongFileCount,
+                    ));
+                }
+                position += c.len_utf8();
+            }
+
+            if file != 8 {
+                return Err(FenError::new(
+                    FenField::Placement,
+                    position,
+                    FenErrorKind::WrongFileCount,
+                ));
+            }
+            position += 1; // '/'
+        }
+
+        // Side to move
+        if let Some(&(start, side)) = fields.get(1) {
+            board.turn = match side {
+                "w" => Color::White,
+                "b" => Color::Black,
+                _ => {
+                    let c = side.chars().find(|&c| c != 'w' && c != 'b').unwrap_or('w');
+                    let offset = side.find(c).unwrap_or(0);
+                    return Err(FenError::new(
+                        FenField::SideToMove,
+                        start + offset,
+                        FenErrorKind::InvalidCharacter(c),
+                    ));
+                }
+            };
+        }
+
+        // Castling rights
+        if let Some(&(start, castling)) = fields.get(2)
+            && castling != "-"
+        {
+            for (offset, c) in castling.char_indices() {
+                let flag = match c {
+                    'K' => WHITE_KINGSIDE,
+                    'Q' => WHITE_QUEENSIDE,
+                    'k' => BLACK_KINGSIDE,
+                    'q' => BLACK_QUEENSIDE,
+                    _ if strict => {
+                        return Err(FenError::new(
+                            FenField::Castling,
+                            start + offset,
+                            FenErrorKind::InvalidCharacter(c),
+                        ));
+                    }
+                    _ => 0,
+                };
+                if strict && board.castling_rights & flag != 0 {
+                    return Err(FenError::new(
+                        FenField::Castling,
+                        start + offset,
+                        FenErrorKind::DuplicateCastling(c),
+                    ));
+                }
+                board.castling_rights |= flag;
+            }
+        }
+
+        // En passant
+        if let Some(&(start, ep)) = fields.get(3)
+            && ep != "-"
+        {
+            let (ep_rank, _) = en_passant_rank(board.turn);
+            let sq = algebraic_to_square(ep)
+                .filter(|sq| sq.rank() == ep_rank)
+                .ok_or(FenError::new(
+                    FenField::EnPassant,
+                    start,
+                    FenErrorKind::InvalidSquare,
+                ))?;
+            board.en_passant = Some(sq);
+        }
+
+        Ok(board)
+    }
+
+    /// Reject positions that cannot arise in a game
+    pub(crate) fn validate(&self, fields: &[(usize, &str)]) -> Result<(), FenError> {
+        let placement = fields[0].0;
+        let castling = fields[2].0;
+        let en_passant = fields[3].0;
+
+        self.validate_kings(placement)?;
+
+        let pawns = self.pieces[Color::White as usize][PieceType::Pawn as usize]
+            | self.pieces[Color::Black as usize][PieceType::Pawn as usize];
+        if !(pawns & (RANK_1 | RANK_8)).is_empty() {
+            return Err(FenError::new(
+                FenField::Placement,
+                placement,
+                FenErrorKind::PawnOnBackRank(lsb(pawns & (RANK_1 | RANK_8))),
+            ));
+        }
+
+        if self.is_in_check(self.turn.opposite()) {
+            return Err(FenError::new(
+                FenField::SideToMove,
+                fields[1].0,
+                FenErrorKind::OpponentInCheck,
+            ));
+        }
+
+        for (color, sides) in CASTLING_PATHS.iter().enumerate() {
+            for path in sides {
+                if self.castling_rights & path.right == 0 {
+                    continue;
+                }
+                let king = self.pieces[color][PieceType::King as usize];
+                let rooks = self.pieces[color][PieceType::Rook as usize];
+                if !get_bit(king, path.king_from) || !get_bit(rooks, path.rook_from) {
+                    let c = match path.right {
+                        WHITE_KINGSIDE => 'K',
+                        WHITE_QUEENSIDE => 'Q',
+                        BLACK_KINGSIDE => 'k',
+                        _ => 'q',
+                    };
+                    let offset = fields[2].1.find(c).unwrap_or(0);
+                    return Err(FenError::new(
+                        FenField::Castling,
+                        castling + offset,
+                        FenErrorKind::CastlingWithoutKingOrRook(c),
+                    ));
+                }
+            }
+        }
+
+        if let Some(ep) = self.en_passant {
+            // The rank was checked while parsing, so both squares are on the board
+            let (_, dy) = en_passant_rank(self.turn);
+            let opp_pawns = self.pieces[self.turn.opposite() as usize][PieceType::Pawn as usize];
+            let pushed_sq = ep.offset(0, dy).expect("en passant rank checked");
+            let origin_sq = ep.offset(0, -dy).expect("en passant rank checked");
+            if !get_bit(opp_pawns, pushed_sq)
+                || get_bit(self.occupied, ep)
+                || get_bit(self.occupied, origin_sq)
+            {
+                return Err(FenError::new(
+                    FenField::EnPassant,
+                    en_passant,
+                    FenErrorKind::ImpossibleEnPassant(ep),
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Reject a placement without exactly one king per side, which move
+    /// generation cannot handle
+    fn validate_kings(&self, placement: usize) -> Result<(), FenError> {
+        for color in [Color::White, Color::Black] {
+            let kings = self.pieces[color as usize][PieceType::King as usize].popcount();
+            if kings != 1 {
+                return Err(FenError::new(
+                    FenField::Placement,
+                    placement,
+                    FenErrorKind::KingCount(color, kings),
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Helper: convert algebraic notation like "e4" to a square; same as `str::parse`
+pub fn algebraic_to_square(alg: &str) -> Option<Square> {
+    alg.parse().ok()
+}
@@ -0,0 +1,212 @@
+use crate::apply_moves::UndoInfo;
+use crate::constants::*;
+use crate::initialize_board::Board;
+use crate::move_list::MoveList;
+use crate::pseudo_legal_move_generation::Move;
+use crate::zobrist::ZOBRIST;
+
+/// Why a game was drawn
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DrawReason {
+    Stalemate,
+    InsufficientMaterial,
+    /// Same position for the fifth time: drawn automatically
+    FivefoldRepetition,
+    /// 75 moves by each side without a capture or pawn move: drawn automatically
+    SeventyFiveMoves,
+    /// Same position for the third time: either player may claim a draw
+    ThreefoldRepetition,
+    /// 50 moves by each side without a capture or pawn move: either player may claim a draw
+    FiftyMoves,
+}
+
+impl DrawReason {
+    /// True for draws a player has to claim; the game goes on otherwise
+    pub fn requires_claim(self) -> bool {
+        matches!(
+            self,
+            DrawReason::ThreefoldRepetition | DrawReason::FiftyMoves
+        )
+    }
+}
+
+/// State of a game after the last move
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameStatus {
+    Ongoing,
+    Checkmate { winner: Color },
+    Draw(DrawReason),
+}
+
+impl GameStatus {
+    /// True once the game has ended without anyone having to claim anything
+    pub fn is_game_over(self) -> bool {
+        match self {
+            GameStatus::Ongoing => false,
+            GameStatus::Checkmate { .. } => true,
+            GameStatus::Draw(reason) => !reason.requires_claim(),
+        }
+    }
+}
+
+impl Board {
+    /// Status from this position alone: everything except repetitions, which need a `Game`
+    pub fn status(&self) -> GameStatus {
+        let mut moves = MoveList::new();
+        self.generate_legal_moves_into(&mut moves);
+        if moves.is_empty() {
+            return if self.is_in_check(self.turn) {
+                GameStatus::Checkmate {
+                    winner: self.turn.opposite(),
+                }
+            } else {
+                GameStatus::Draw(DrawReason::Stalemate)
+            };
+        }
+        if self.is_insufficient_material() {
+            return GameStatus::Draw(DrawReason::InsufficientMaterial);
+        }
+        if self.half_moves >= 150 {
+            return GameStatus::Draw(DrawReason::SeventyFiveMoves);
+        }
+        if self.half_moves >= 100 {
+            return GameStatus::Draw(DrawReason::FiftyMoves);
+        }
+        GameStatus::Ongoing
+    }
+
+    pub fn is_checkmate(&self) -> bool {
+        matches!(self.status(), GameStatus::Checkmate { .. })
+    }
+
+    pub fn is_stalemate(&self) -> bool {
+        self.status() == GameStatus::Draw(DrawReason::Stalemate)
+    }
+
+    /// Neither side can possibly mate: bare kings, a single minor piece,
+    /// or only bishops that all stand on squares of one colour
+    pub fn is_insufficient_material(&self) -> bool {
+        let mut knights = Bitboard::EMPTY;
+        let mut bishops = Bitboard::EMPTY;
+        for color in [Color::White, Color::Black] {
+            let pieces = &self.pieces[color as usize];
+            if !(pieces[PieceType::Pawn as usize]
+                | pieces[PieceType::Rook as usize]
+                | pieces[PieceType::Queen as usize])
+                .is_empty()
+            {
+                return false;
+            }
+            knights |= pieces[PieceType::Knight as usize];
+            bishops |= pieces[PieceType::Bishop as usize];
+        }
+
+        if !(knights | bishops).more_than_one() {
+            return true;
+        }
+        knights.is_empty()
+            && ((bishops & LIGHT_SQUARES).is_empty() || (bishops & DARK_SQUARES).is_empty())
+    }
+
+    /// Hash used to compare positions for repetition: an en passant square only
+    /// counts when the capture is actually legal
+    pub fn repetition_key(&self) -> u64 {
+        match self.en_passant {
+            Some(ep) if self.legal_en_passant().is_none() => self.hash ^ ZOBRIST.en_passant(ep),
+            _ => self.hash,
+        }
+    }
+}
+
+/// A board plus the moves that led to it, so repetitions can be detected and moves taken back
+#[derive(Debug, Clone)]
+pub struct Game {
+    board: Board,
+    /// Repetition keys of every position so far, current one last.
+    /// Only the entries since the last irreversible move (`board.half_moves`) are ever compared.
+    keys: Vec<u64>,
+    moves: Vec<(Move, UndoInfo)>,
+}
+
+impl Default for Game {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Game {
+    /// A game from the standard starting position
+    pub fn new() -> Self {
+        Self::from_board(Board::new())
+    }
+
+    /// A game starting from any position, e.g. one loaded with `Board::from_fen`
+    pub fn from_board(board: Board) -> Self {
+        Game {
+            board,
+            keys: vec![board.repetition_key()],
+            moves: Vec::new(),
+        }
+    }
+
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    /// Moves played so far, oldest first
+    pub fn moves(&self) -> impl Iterator<Item = &Move> {
+        self.moves.iter().map(|(m, _)| m)
+    }
+
+    /// Play a move; it must be legal in the current position
+    pub fn make_move(&mut self, m: &Move) {
+        let undo = self.board.make_move(m);
+        self.moves.push((*m, undo));
+        self.keys.push(self.board.repetition_key());
+    }
+
+    /// Take back the last move, returning it (None at the start of the game)
+    pub fn take_back(&mut self) -> Option<Move> {
+        let (m, undo) = self.moves.pop()?;
+        self.board.unmake_move(&m, &undo);
+        self.keys.pop();
+        Some(m)
+    }
+
+    /// How many times the current position has occurred, counting this time
+    pub fn repetition_count(&self) -> usize {
+        let current = *self.keys.last().unwrap();
+        let window = (self.board.half_moves as usize + 1).min(self.keys.len());
+        self.keys[self.keys.len() - window..]
+            .iter()
+            .rev()
+            .step_by(2) // same side to move
+            .filter(|&&key| key == current)
+            .count()
+    }
+
+    /// Checkmate, stalemate, move-count rules, repetitions and insufficient material
+    pub fn status(&self) -> GameStatus {
+        let status = self.board.status();
+        if matches!(
+            status,
+            GameStatus::Checkmate { .. }
+                | GameStatus::Draw(DrawReason::Stalemate)
+                | GameStatus::Draw(DrawReason::InsufficientMaterial)
+        ) {
+            return status;
+        }
+
+        let repetitions = self.repetition_count();
+        if repetitions >= 5 {
+            return GameStatus::Draw(DrawReason::FivefoldRepetition);
+        }
+        if status == GameStatus::Draw(DrawReason::SeventyFiveMoves) {
+            return status;
+        }
+        if repetitions >= 3 {
+            return GameStatus::Draw(DrawReason::ThreefoldRepetition);
+        }
+        status
+    }
+}
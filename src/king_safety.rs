@@ -0,0 +1,110 @@
+use crate::attack::KING_ATTACKS;
+use crate::constants::*;
+use crate::eval::{PIECE_VALUES, Score};
+use crate::initialize_board::Board;
+use crate::pawn_structure::{ranks_ahead, relative_rank};
+use crate::pseudo_legal_move_generation::piece_attacks;
+use crate::square::File;
+
+/// Shelter bonus by the relative rank of our nearest pawn in front of the
+/// king on a file; index 0 means there is none
+const SHELTER: [i32; 8] = [-30, 30, 20, 6, 0, 0, 0, 0];
+/// Storm penalty by the relative rank (from our side) of their nearest pawn
+/// in front of our king on a file; index 0 means there is none
+const STORM: [i32; 8] = [0, 0, -35, -25, -12, -4, 0, 0];
+/// Storm penalty instead, when the storming pawn is stuck right in front of our shelter pawn
+const STORM_BLOCKED: i32 = -8;
+
+/// Danger units per king-zone square attacked, by attacker type
+const ZONE_ATTACK: [i32; 6] = [0, 2, 2, 3, 5, 0];
+/// Danger units when a piece of this type can give a check the defender does not cover
+const SAFE_CHECK: [i32; 6] = [0, 10, 6, 11, 10, 0];
+/// Danger units for each file around the king without our pawns, and extra
+/// when it has no pawns at all
+const SEMI_OPEN_FILE: i32 = 3;
+const OPEN_FILE: i32 = 3;
+
+/// Non-pawn material of one side at the start, by midgame values
+const START_MATERIAL: i32 = 2 * 337 + 2 * 365 + 2 * 477 + 1025;
+
+impl Board {
+    /// King safety of `color`'s king, from `color`'s point of view: pawn
+    /// shelter and storm in the midgame, less the danger from enemy attacks,
+    /// scaled by how much material the attacker has left
+    pub fn king_safety(&self, color: Color) -> Score {
+        let Some(king) = self.pieces[color as usize][PieceType::King as usize].lsb() else {
+            return Score::ZERO;
+        };
+        let them = color.opposite();
+        let our_pawns = self.pieces[color as usize][PieceType::Pawn as usize];
+        let their_pawns = self.pieces[them as usize][PieceType::Pawn as usize];
+
+        // Shelter and storm on the king's file and its neighbours, kept off the edge
+        let in_front = ranks_ahead(color, king) | Bitboard::rank(king.rank());
+        let center = king.file().index().clamp(1, 6);
+        let mut shelter = 0;
+        let mut units = 0;
+        for f in center - 1..=center + 1 {
+            let file = Bitboard::file(File::new(f as u8).unwrap());
+            let ours = file & our_pawns & in_front;
+            let theirs = file & their_pawns & in_front;
+            let nearest = |pawns: Bitboard| match color {
+                Color::White => pawns.lsb(),
+                Color::Black => pawns.into_iter().last(),
+            };
+            let own_rank = nearest(ours).map_or(0, |sq| relative_rank(color, sq));
+            let storm_rank = nearest(theirs).map_or(0, |sq| relative_rank(color, sq));
+            shelter += SHELTER[own_rank];
+            shelter += if own_rank != 0 && storm_rank == own_rank + 1 {
+                STORM_BLOCKED
+            } else {
+                STORM[storm_rank]
+            };
+
+            if (file & our_pawns).is_empty() {
+                units += SEMI_OPEN_FILE;
+                if (file & their_pawns).is_empty() {
+                    units += OPEN_FILE;
+                }
+            }
+        }
+
+        // Enemy pieces bearing on the king zone, and checks we do not cover
+        let zone = KING_ATTACKS[king.index()] | king.bit();
+        let defended = self.attacked_squares(color);
+        let check_targets = !self.all_pieces(them) & !defended;
+        let mut attackers = 0;
+        let mut zone_units = 0;
+        let mut material = 0;
+        for piece_type in [
+            PieceType::Knight,
+            PieceType::Bishop,
+            PieceType::Rook,
+            PieceType::Queen,
+        ] {
+            let pt = piece_type as usize;
+            let mut reach = Bitboard::EMPTY;
+            for sq in self.pieces[them as usize][pt] {
+                let attacks = piece_attacks(piece_type, them, sq, self.occupied);
+                let hits = attacks & zone;
+                if !hits.is_empty() {
+                    attackers += 1;
+                    zone_units += ZONE_ATTACK[pt] * hits.popcount() as i32;
+                }
+                reach |= attacks;
+                material += PIECE_VALUES[pt].mg;
+            }
+            let checks = piece_attacks(piece_type, color, king, self.occupied) & check_targets;
+            if !(checks & reach).is_empty() {
+                units += SAFE_CHECK[pt];
+            }
+        }
+        // A lone attacker is rarely dangerous
+        if attackers >= 2 {
+            units += zone_units;
+        }
+
+        let danger = units * units / 16 * material.min(START_MATERIAL) / START_MATERIAL;
+        Score::new(shelter - danger, -danger / 4)
+    }
+}
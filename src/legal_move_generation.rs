@@ -1,23 +1,229 @@
+use crate::attack::*;
+use crate::constants::*;
 use crate::initialize_board::*;
+use crate::magic::*;
+use crate::move_list::MoveList;
+use crate::pawn_directions::*;
 use crate::pseudo_legal_move_generation::Move;
+use crate::utils::*;
+
+const PROMOTION_PIECES: [PieceType; 4] = [
+    PieceType::Queen,
+    PieceType::Rook,
+    PieceType::Bishop,
+    PieceType::Knight,
+];
 
 impl Board {
+    /// Generate only legal moves.
+    ///
+    /// Checkers and pinned pieces are worked out up front, so no move has to be
+    /// made and tested:
+    /// - under double check only the king may move
+    /// - under single check every other move must capture the checker or block
+    /// - a pinned piece may only move along the line between its king and the pinner
     pub fn generate_legal_moves(&self) -> Vec<Move> {
-        let pseudo_moves = self.generate_pseudo_moves();
+        let mut moves = MoveList::new();
+        self.generate_legal_moves_into(&mut moves);
+        moves.to_vec()
+    }
+
+    /// Like `generate_legal_moves`, but appends to a caller-provided list
+    pub fn generate_legal_moves_into(&self, moves: &mut MoveList) {
+        let color = self.turn;
+        let opp_color = color.opposite();
+
+        let own_pieces = self.all_pieces(color);
+        let opp_pieces = self.all_pieces(opp_color);
+        let occupied = self.occupied;
+        let empty = !occupied;
+
+        let king_sq = self.king_square(color);
+        let checkers = self.attackers_by(king_sq, opp_color, occupied);
+
+        // Squares every non-king move has to land on
+        let check_mask = if checkers.is_empty() {
+            Bitboard::FULL
+        } else if checkers.more_than_one() {
+            Bitboard::EMPTY
+        } else {
+            checkers | between(king_sq, lsb(checkers))
+        };
+
+        // Pinned pieces: exactly one of our pieces between the king and an enemy slider
+        let theirs = &self.pieces[opp_color as usize];
+        let snipers = (bishop_attacks(king_sq, opp_pieces)
+            & (theirs[PieceType::Bishop as usize] | theirs[PieceType::Queen as usize]))
+            | (rook_attacks(king_sq, opp_pieces)
+                & (theirs[PieceType::Rook as usize] | theirs[PieceType::Queen as usize]));
+        let mut pinned = Bitboard::EMPTY;
+        for sniper in snipers {
+            let blockers = between(king_sq, sniper) & occupied;
+            if !blockers.more_than_one() && !(blockers & own_pieces).is_empty() {
+                pinned |= blockers;
+            }
+        }
+
+        // A pinned piece keeps the king shielded only on the king-pinner line
+        let allowed = |from: Square| -> Bitboard {
+            if get_bit(pinned, from) {
+                line(king_sq, from)
+            } else {
+                Bitboard::FULL
+            }
+        };
+
+        // helper to add normal moves
+        macro_rules! add_moves {
+            ($from:expr, $targets:expr) => {
+                for to in $targets {
+                    let flags = if get_bit(opp_pieces, to) {
+                        Move::CAPTURE
+                    } else {
+                        Move::QUIET
+                    };
+                    moves.push(Move::new($from, to, flags));
+                }
+            };
+        }
+
+        // helper to add pawn moves, expanding promotions
+        macro_rules! add_pawn_move {
+            ($from:expr, $to:expr, $flags:expr) => {
+                if get_bit(RANK_1 | RANK_8, $to) {
+                    let capture = $flags & Move::CAPTURE != 0;
+                    for &promo in &PROMOTION_PIECES {
+                        moves.push(Move::new_promotion($from, $to, promo, capture));
+                    }
+                } else {
+                    moves.push(Move::new($from, $to, $flags));
+                }
+            };
+        }
+
+        if !check_mask.is_empty() {
+            // === PAWNS ===
+            let pawns = self.pieces[color as usize][PieceType::Pawn as usize];
+            let direction: i8 = if color == Color::White { 8 } else { -8 };
+            let double_push_rank = if color == Color::White {
+                RANK_4
+            } else {
+                RANK_5
+            };
+
+            for from in pawns {
+                let from_bb = from.bit();
+                let pin_mask = allowed(from);
+
+                // Pushes
+                let single = shift(from_bb, direction) & empty;
+                let double = shift(single, direction) & empty & double_push_rank;
+                for to in (single | double) & check_mask & pin_mask {
+                    let flags = if get_bit(double, to) {
+                        Move::DOUBLE_PUSH
+                    } else {
+                        Move::QUIET
+                    };
+                    add_pawn_move!(from, to, flags);
+                }
+
+                // Captures
+                for to in pawn_attacks(from_bb, color) & opp_pieces & check_mask & pin_mask {
+                    add_pawn_move!(from, to, Move::CAPTURE);
+                }
+
+                // En passant: the captured pawn may be the checker, and removing two
+                // pawns from one rank can expose the king, so test the resulting occupancy
+                if let Some(ep) = self.en_passant
+                    && pawn_attacks(from_bb, color).contains(ep)
+                {
+                    let captured_sq = Square::from_coords(ep.file(), from.rank());
+                    let after = (occupied & !from_bb & !captured_sq.bit()) | ep.bit();
+                    let attackers =
+                        self.attackers_by(king_sq, opp_color, after) & !captured_sq.bit();
+                    if attackers.is_empty() {
+                        moves.push(Move::new(from, ep, Move::EN_PASSANT));
+                    }
+                }
+            }
+
+            // === KNIGHTS === (a pinned knight can never move)
+            for from in self.pieces[color as usize][PieceType::Knight as usize] & !pinned {
+                let attacks = KNIGHT_ATTACKS[from.index()] & !own_pieces & check_mask;
+                add_moves!(from, attacks);
+            }
+
+            // === BISHOPS ===
+            for from in self.pieces[color as usize][PieceType::Bishop as usize] {
+                let attacks =
+                    bishop_attacks(from, occupied) & !own_pieces & check_mask & allowed(from);
+                add_moves!(from, attacks);
+            }
+
+            // === ROOKS ===
+            for from in self.pieces[color as usize][PieceType::Rook as usize] {
+                let attacks =
+                    rook_attacks(from, occupied) & !own_pieces & check_mask & allowed(from);
+                add_moves!(from, attacks);
+            }
+
+            // === QUEENS ===
+            for from in self.pieces[color as usize][PieceType::Queen as usize] {
+                let attacks =
+                    queen_attacks(from, occupied) & !own_pieces & check_mask & allowed(from);
+                add_moves!(from, attacks);
+            }
+        }
+
+        // === KING ===
+        // Sliders must see through the king, or it could step back along a checking ray
+        let without_king = occupied & !king_sq.bit();
+        for to in KING_ATTACKS[king_sq.index()] & !own_pieces {
+            if self.attackers_by(to, opp_color, without_king).is_empty() {
+                add_moves!(king_sq, to.bit());
+            }
+        }
+
+        // === CASTLING === (generated fully legal)
+        if checkers.is_empty() {
+            self.generate_castling_moves(moves);
+        }
+    }
+
+    /// The en passant square, but only if an en passant capture is actually legal
+    pub fn legal_en_passant(&self) -> Option<Square> {
+        let ep = self.en_passant?;
+        let pawns = self.pieces[self.turn as usize][PieceType::Pawn as usize];
+        // Cheap pre-check: some pawn must attack the square at all
+        if (pawn_attacks(ep.bit(), self.turn.opposite()) & pawns).is_empty() {
+            return None;
+        }
+        let mut moves = MoveList::new();
+        self.generate_legal_moves_into(&mut moves);
+        moves.iter().any(|m| m.is_en_passant()).then_some(ep)
+    }
+
+    /// Legal moves by making every pseudo-legal move and testing for check.
+    /// Much slower than `generate_legal_moves`; kept as a reference to cross-check it.
+    pub fn generate_legal_moves_by_filter(&self) -> Vec<Move> {
+        let mut pseudo_moves = MoveList::new();
+        self.generate_pseudo_moves_into(&mut pseudo_moves);
         let mut legal_moves = Vec::with_capacity(pseudo_moves.len());
 
         let original_turn = self.turn;
 
-        for &m in &pseudo_moves {
-            // Create a temporary copy of the board
-            let mut temp_board = *self;
+        // One scratch copy; each move is made and taken back on it
+        let mut board = *self;
 
-            temp_board.apply_move(&m);
+        for &m in &pseudo_moves {
+            let undo = board.make_move(&m);
 
             // After the move, it's the opponent's turn - check if our king is in check
-            if !temp_board.is_in_check(original_turn) {
+            if !board.is_in_check(original_turn) {
                 legal_moves.push(m);
             }
+
+            board.unmake_move(&m, &undo);
         }
         legal_moves
     }
@@ -1,19 +1,51 @@
 pub mod apply_moves;
 pub mod attack;
+pub mod bitboard;
 pub mod constants;
+pub mod epd;
+pub mod eval;
+pub mod fen;
+pub mod game;
 pub mod initialize_board;
+pub mod king_safety;
 pub mod legal_move_generation;
+pub mod magic;
+pub mod move_list;
 pub mod pawn_directions;
+pub mod pawn_structure;
+pub mod perft;
+pub mod pgn;
+pub mod piece_activity;
 pub mod print_board;
 pub mod pseudo_legal_move_generation;
+pub mod san;
+pub mod see;
+pub mod square;
+pub mod staged_move_generation;
+pub mod uci;
 pub mod utils;
+pub mod zobrist;
 
 pub use apply_moves::*;
 pub use attack::*;
+pub use bitboard::*;
 pub use constants::*;
+pub use epd::*;
+pub use eval::*;
+pub use fen::*;
+pub use game::*;
 pub use initialize_board::*;
-pub use legal_move_generation::*;
+pub use magic::*;
+pub use move_list::*;
 pub use pawn_directions::*;
-pub use print_board::*;
+pub use pawn_structure::*;
+pub use pgn::*;
+pub use piece_activity::*;
 pub use pseudo_legal_move_generation::*;
+pub use san::*;
+pub use see::*;
+pub use square::*;
+pub use staged_move_generation::*;
+pub use uci::*;
 pub use utils::*;
+pub use zobrist::*;
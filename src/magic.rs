@@ -0,0 +1,325 @@
+use std::sync::LazyLock;
+
+use crate::constants::*;
+use crate::square::*;
+use crate::utils::*;
+
+// =====================
+// Reference ray attacks
+// =====================
+// Simple, slow, obviously-correct attack generation. Used to build the magic
+// tables and to cross-check them.
+
+/// Generic ray in one direction until blocked or edge
+fn ray_attack(sq: Square, dx: i8, dy: i8, occupied: Bitboard) -> Bitboard {
+    let mut attacks = Bitboard::EMPTY;
+    let mut cur = sq;
+    while let Some(next) = cur.offset(dx, dy) {
+        set_bit(&mut attacks, next);
+        if get_bit(occupied, next) {
+            break; // blocked by any piece
+        }
+        cur = next;
+    }
+    attacks
+}
+
+/// Bishop attacks by walking the four diagonal rays
+pub fn bishop_attacks_ray(sq: Square, occupied: Bitboard) -> Bitboard {
+    ray_attack(sq, 1, 1, occupied)
+        | ray_attack(sq, -1, -1, occupied)
+        | ray_attack(sq, -1, 1, occupied)
+        | ray_attack(sq, 1, -1, occupied)
+}
+
+/// Rook attacks by walking the four orthogonal rays
+pub fn rook_attacks_ray(sq: Square, occupied: Bitboard) -> Bitboard {
+    ray_attack(sq, 1, 0, occupied)
+        | ray_attack(sq, -1, 0, occupied)
+        | ray_attack(sq, 0, 1, occupied)
+        | ray_attack(sq, 0, -1, occupied)
+}
+
+/// Queen attacks by walking all eight rays
+pub fn queen_attacks_ray(sq: Square, occupied: Bitboard) -> Bitboard {
+    bishop_attacks_ray(sq, occupied) | rook_attacks_ray(sq, occupied)
+}
+
+// =====================
+// Magic bitboards
+// =====================
+
+/// Per-square lookup data for one slider type.
+/// With PEXT only `mask` and `offset` are used.
+#[derive(Clone, Copy, Default)]
+#[cfg_attr(
+    all(feature = "pext", target_arch = "x86_64", target_feature = "bmi2"),
+    allow(dead_code)
+)]
+struct Magic {
+    /// Relevant blocker squares (edges excluded)
+    mask: Bitboard,
+    /// Multiplier mapping blocker subsets to table indices
+    magic: u64,
+    /// 64 - number of relevant bits
+    shift: u32,
+    /// Start of this square's slice in the shared table
+    offset: usize,
+}
+
+impl Magic {
+    #[inline(always)]
+    fn index(&self, occupied: Bitboard) -> usize {
+        #[cfg(all(feature = "pext", target_arch = "x86_64", target_feature = "bmi2"))]
+        {
+            // SAFETY: guarded by target_feature = "bmi2"
+            let idx = unsafe { std::arch::x86_64::_pext_u64(occupied.0, self.mask.0) };
+            self.offset + idx as usize
+        }
+        #[cfg(not(all(feature = "pext", target_arch = "x86_64", target_feature = "bmi2")))]
+        {
+            let idx = ((occupied & self.mask).0.wrapping_mul(self.magic)) >> self.shift;
+            self.offset + idx as usize
+        }
+    }
+}
+
+struct SliderTable {
+    magics: [Magic; 64],
+    attacks: Vec<Bitboard>,
+}
+
+// Found with a sparse random search: for each square, candidates were tried
+// until one mapped every blocker subset without a destructive collision.
+/// Bishop magic multipliers, one per square (A1..H8)
+const BISHOP_MAGICS: [u64; 64] = [
+    0x2420_0210_4040_8280,
+    0x4004_1412_e401_000a,
+    0x2011_2806_0040_0020,
+    0x0004_0410_8101_8220,
+    0x0284_0420_1000_0c00,
+    0x0011_0120_1088_0051,
+    0x0008_8848_0804_00c4,
+    0x0800_8048_00d0_0800,
+    0x0201_2028_1240_8418,
+    0x0000_0408_d800_4080,
+    0x0000_080a_046e_0030,
+    0x0080_2407_0600_810e,
+    0x9016_0202_1001_0000,
+    0x0408_0310_4804_0410,
+    0x1100_0084_1008_0400,
+    0x0160_2080_8808_0208,
+    0x0009_0172_1001_0800,
+    0x0808_0020_3109_4201,
+    0x1082_0010_0026_0020,
+    0x0000_81a8_0281_0009,
+    0x1003_0018_2008_1001,
+    0x6002_0224_8801_0800,
+    0x0040_4014_0908_2800,
+    0x2022_1010_8051_0800,
+    0x0820_100a_0410_0220,
+    0x4404_0200_2002_4400,
+    0x1008_0208_0404_0615,
+    0x0001_0040_0400_4200,
+    0x20b0_8480_0400_2004,
+    0x8a08_0021_0200_8422,
+    0x0110_8880_2424_0400,
+    0x00a0_8100_c080_4830,
+    0x0210_1004_006b_0801,
+    0x1049_0820_0403_2c21,
+    0x0102_0024_0812_0800,
+    0x4008_2008_0009_0104,
+    0x0001_2104_0002_0020,
+    0x5000_9102_0005_0091,
+    0x0024_1904_0804_4420,
+    0x2008_3110_40a3_0a40,
+    0x2012_0104_4000_2140,
+    0x0002_1482_1890_2000,
+    0x0101_8040_4040_0810,
+    0x0004_1220_1101_0800,
+    0x1804_0801_0400_0040,
+    0x0201_2012_0411_0480,
+    0x00a0_4144_0320_8080,
+    0x0402_4a04_0300_4020,
+    0x0001_0842_1004_3121,
+    0x2048_4100_9010_0000,
+    0x2024_020a_0104_0000,
+    0x1880_00a2_0a02_0000,
+    0x1004_2040_4501_0000,
+    0x0808_0411_420a_0400,
+    0x8004_2004_4404_8800,
+    0x2810_1401_5402_2003,
+    0x2084_1202_2104_4000,
+    0x8004_0242_0084_a021,
+    0x0009_44c1_4608_0408,
+    0x2000_c400_0104_0900,
+    0x1001_0100_1002_0880,
+    0x001a_0040_9009_4241,
+    0x2080_2004_5080_a300,
+    0x8050_1009_9800_40ca,
+];
+
+/// Rook magic multipliers, one per square (A1..H8)
+const ROOK_MAGICS: [u64; 64] = [
+    0x0480_04a1_1040_0280,
+    0x0040_0010_0020_0048,
+    0x2100_1020_0100_0840,
+    0x4080_0800_1000_8005,
+    0x4100_0500_1008_0002,
+    0x2100_0100_0804_0002,
+    0x0400_0210_0801_0084,
+    0x5080_0080_0820_d500,
+    0x0002_0022_0880_4102,
+    0x0101_0020_8100_4000,
+    0x0082_0012_2242_0480,
+    0x0684_8080_1000_0800,
+    0x0001_0008_0100_1004,
+    0x0830_8080_8200_0400,
+    0x4021_0100_0402_0001,
+    0x8001_0000_a04d_0012,
+    0x8080_0040_0020_0042,
+    0x8020_0080_8020_4000,
+    0x8500_8480_2000_1000,
+    0x2201_8280_0800_1000,
+    0x2000_8280_0800_0400,
+    0x8000_8080_0400_0200,
+    0x2000_8080_0100_0200,
+    0x008c_0200_0040_9104,
+    0x1000_8202_0021_0040,
+    0x0000_2081_0040_0101,
+    0x2010_0801_2000_2400,
+    0x0192_8901_0010_0420,
+    0x1c08_0501_0010_0801,
+    0x2002_0004_0400_2010,
+    0x0000_0864_0010_0201,
+    0x8102_0082_0001_0044,
+    0x0180_0020_0440_0040,
+    0x0000_8240_1180_2000,
+    0x0026_0444_8200_1021,
+    0xc045_0010_0100_0820,
+    0x0c22_0020_0600_1008,
+    0x6000_0200_8080_0400,
+    0x0028_8210_0400_0801,
+    0x8021_8000_6080_0100,
+    0x1a80_0041_2008_c002,
+    0x0200_5000_2000_4004,
+    0x0a10_4010_8202_0024,
+    0xe018_1004_2101_0009,
+    0x20c0_0801_0005_0010,
+    0x0a85_0002_0401_0008,
+    0x0982_0004_0802_0001,
+    0xae00_0049_0882_0004,
+    0x00a0_4020_8001_1100,
+    0x4880_2200_4900_8a00,
+    0x0000_9000_a000_8480,
+    0x0610_0800_8010_0080,
+    0x0024_0008_0004_8080,
+    0x280a_0010_0408_0200,
+    0x0300_9601_1028_0c00,
+    0x0801_0010_8208_e100,
+    0x2008_6080_0011_4501,
+    0x0042_0010_8021_0042,
+    0x2002_0008_4010_8022,
+    0x8010_2004_0810_0101,
+    0x2002_0021_0804_1002,
+    0x4022_0001_0408_1002,
+    0x1080_8081_1022_1814,
+    0x0000_0081_0400_2042,
+];
+
+/// Relevant occupancy mask: the empty-board attacks minus the board edges a ray ends on
+fn relevant_mask(sq: Square, attacks_fn: fn(Square, Bitboard) -> Bitboard) -> Bitboard {
+    let mut edges = Bitboard::EMPTY;
+    if sq.file() != File::A {
+        edges |= FILE_A;
+    }
+    if sq.file() != File::H {
+        edges |= FILE_H;
+    }
+    if sq.rank() != Rank::ONE {
+        edges |= RANK_1;
+    }
+    if sq.rank() != Rank::EIGHT {
+        edges |= RANK_8;
+    }
+    attacks_fn(sq, Bitboard::EMPTY) & !edges
+}
+
+impl SliderTable {
+    fn build(attacks_fn: fn(Square, Bitboard) -> Bitboard, magic_numbers: &[u64; 64]) -> Self {
+        let mut magics = [Magic::default(); 64];
+        let mut attacks = Vec::new();
+
+        for sq in Square::all() {
+            let mask = relevant_mask(sq, attacks_fn);
+            let bits = mask.popcount();
+            let size = 1usize << bits;
+
+            let offset = attacks.len();
+            attacks.resize(offset + size, Bitboard::EMPTY);
+
+            let entry = Magic {
+                mask,
+                magic: magic_numbers[sq.index()],
+                shift: 64 - bits,
+                offset,
+            };
+
+            // Enumerate every blocker subset of the mask (Carry-Rippler)
+            let mut subset = Bitboard::EMPTY;
+            loop {
+                let idx = entry.index(subset);
+                let att = attacks_fn(sq, subset);
+                debug_assert!(
+                    attacks[idx].is_empty() || attacks[idx] == att,
+                    "destructive magic collision on square {sq}"
+                );
+                attacks[idx] = att;
+
+                subset = Bitboard(subset.0.wrapping_sub(mask.0)) & mask;
+                if subset.is_empty() {
+                    break;
+                }
+            }
+
+            magics[sq.index()] = entry;
+        }
+
+        SliderTable { magics, attacks }
+    }
+
+    #[inline(always)]
+    fn attacks(&self, sq: Square, occupied: Bitboard) -> Bitboard {
+        self.attacks[self.magics[sq.index()].index(occupied)]
+    }
+}
+
+static BISHOP_TABLE: LazyLock<SliderTable> =
+    LazyLock::new(|| SliderTable::build(bishop_attacks_ray, &BISHOP_MAGICS));
+
+static ROOK_TABLE: LazyLock<SliderTable> =
+    LazyLock::new(|| SliderTable::build(rook_attacks_ray, &ROOK_MAGICS));
+
+/// Build the slider tables now instead of on the first lookup
+pub fn init_slider_tables() {
+    LazyLock::force(&BISHOP_TABLE);
+    LazyLock::force(&ROOK_TABLE);
+}
+
+/// Bishop attacks from `sq` given the board occupancy
+#[inline(always)]
+pub fn bishop_attacks(sq: Square, occupied: Bitboard) -> Bitboard {
+    BISHOP_TABLE.attacks(sq, occupied)
+}
+
+/// Rook attacks from `sq` given the board occupancy
+#[inline(always)]
+pub fn rook_attacks(sq: Square, occupied: Bitboard) -> Bitboard {
+    ROOK_TABLE.attacks(sq, occupied)
+}
+
+/// Queen attacks from `sq` given the board occupancy
+#[inline(always)]
+pub fn queen_attacks(sq: Square, occupied: Bitboard) -> Bitboard {
+    bishop_attacks(sq, occupied) | rook_attacks(sq, occupied)
+}
@@ -1,12 +1,12 @@
-use my_own_chess_engine::attack::*;
 use my_own_chess_engine::constants::*;
 use my_own_chess_engine::initialize_board::*;
+use my_own_chess_engine::square::*;
 use my_own_chess_engine::utils::*;
 
 fn main() {
-    let mut bb: Bitboard = 0;
-    set_bit(&mut bb, 0); // Set A1
-    assert!(get_bit(bb, 0));
+    let mut bb = Bitboard::EMPTY;
+    set_bit(&mut bb, A1);
+    assert!(get_bit(bb, A1));
 
     // Initial chess position
     // let board =
@@ -30,9 +30,8 @@ fn main() {
     //     Board::from_fen("r1bqk2r/ppp2ppp/2n2n2/3pp3/3PP3/2NB1N2/PPPQ1PPP/R3K2R w KQkq - 0 1")
     //         .unwrap();
 
-    let mut board =
-        Board::from_fen("r1bqkbnr/pppp1ppp/2n5/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R b KQkq - 1 3")
-            .unwrap();
+    let board = Board::from_fen("r1bqkbnr/pppp1ppp/2n5/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R b KQkq - 1 3")
+        .unwrap();
     println!("Starting position loaded!");
     println!("{:?}", board);
     board.print_board();
@@ -19,27 +19,32 @@ pub fn shift(bb: Bitboard, dir: i8) -> Bitboard {
     }
 }
 
-/// Shift while masking out file wraps (e.g., for pawn captures)
-/// NOT_A_FILE and NOT_H_FILE are masks excluding those files
-pub const NOT_A_FILE: Bitboard = 0xfefefefefefefefe; // ~0x0101010101010101 << 0
-pub const NOT_H_FILE: Bitboard = 0x7f7f7f7f7f7f7f7f; // ~0x8080808080808080
-
 #[inline]
 pub fn shift_north_west(bb: Bitboard) -> Bitboard {
-    (bb << 7) & NOT_A_FILE
+    bb.north_west()
 }
 
 #[inline]
 pub fn shift_north_east(bb: Bitboard) -> Bitboard {
-    (bb << 9) & NOT_H_FILE
+    bb.north_east()
 }
 
 #[inline]
 pub fn shift_south_west(bb: Bitboard) -> Bitboard {
-    (bb >> 9) & NOT_A_FILE
+    bb.south_west()
 }
 
 #[inline]
 pub fn shift_south_east(bb: Bitboard) -> Bitboard {
-    (bb >> 7) & NOT_H_FILE
+    bb.south_east()
+}
+
+/// All squares attacked by the given pawns of `color`
+#[inline]
+pub fn pawn_attacks(pawns: Bitboard, color: Color) -> Bitboard {
+    if color == Color::White {
+        shift_north_west(pawns) | shift_north_east(pawns)
+    } else {
+        shift_south_west(pawns) | shift_south_east(pawns)
+    }
 }
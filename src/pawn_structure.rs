@@ -0,0 +1,320 @@
+use crate::constants::*;
+use crate::eval::Score;
+use crate::initialize_board::Board;
+use crate::pawn_directions::pawn_attacks;
+
+/// A pawn-structure feature, as found by `Board::pawn_report`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PawnFeature {
+    /// A pawn of the same color stands in front of it on its file
+    Doubled,
+    /// No pawns of the same color on the neighbouring files
+    Isolated,
+    /// No neighbour beside or behind it to support its advance, and an enemy
+    /// pawn holds its stop square
+    Backward,
+    /// A pawn of the same color stands beside it
+    Phalanx,
+    /// Defended by a pawn of the same color: a link in a pawn chain
+    Supported,
+    /// Part of a phalanx or supported
+    Connected,
+    /// No enemy pawn in front of it on its own or a neighbouring file,
+    /// and no pawn of its own in front of it either
+    Passed,
+}
+
+impl PawnFeature {
+    pub const ALL: [PawnFeature; 7] = [
+        PawnFeature::Doubled,
+        PawnFeature::Isolated,
+        PawnFeature::Backward,
+        PawnFeature::Phalanx,
+        PawnFeature::Supported,
+        PawnFeature::Connected,
+        PawnFeature::Passed,
+    ];
+}
+
+const DOUBLED: Score = Score::new(-11, -51);
+const ISOLATED: Score = Score::new(-5, -15);
+const BACKWARD: Score = Score::new(-9, -24);
+/// Connected pawn bonus by relative rank, doubled for a phalanx
+const CONNECTED: [i32; 8] = [0, 7, 8, 12, 29, 48, 86, 0];
+/// Added to the connected bonus per defending pawn
+const SUPPORTER: i32 = 21;
+/// Passed pawn bonus by relative rank
+const PASSED: [Score; 8] = [
+    Score::new(0, 0),
+    Score::new(7, 27),
+    Score::new(16, 32),
+    Score::new(17, 40),
+    Score::new(64, 71),
+    Score::new(170, 174),
+    Score::new(278, 262),
+    Score::new(0, 0),
+];
+/// Per rank beyond the third, for a passed pawn whose stop square is occupied
+const PASSED_BLOCKED: Score = Score::new(-4, -10);
+/// Endgame weights of the kings' distances to a passed pawn's stop square,
+/// per rank beyond the third
+const PASSED_OWN_KING: i32 = -2;
+const PASSED_THEIR_KING: i32 = 5;
+
+/// Rank counted from `color`'s own side: 0 for its back rank, 7 for promotion
+#[inline]
+pub fn relative_rank(color: Color, sq: Square) -> usize {
+    match color {
+        Color::White => sq.rank().index(),
+        Color::Black => 7 - sq.rank().index(),
+    }
+}
+
+/// Every square on the ranks in front of `sq`, seen from `color`'s side
+#[inline]
+pub fn ranks_ahead(color: Color, sq: Square) -> Bitboard {
+    let rank = sq.rank().index() as u32;
+    match color {
+        Color::White => Bitboard(u64::MAX.checked_shl(8 * (rank + 1)).unwrap_or(0)),
+        Color::Black => Bitboard((1u64 << (8 * rank)) - 1),
+    }
+}
+
+/// The files either side of `sq`'s file
+#[inline]
+pub fn adjacent_files(sq: Square) -> Bitboard {
+    let file = Bitboard::file(sq.file());
+    file.east() | file.west()
+}
+
+/// The square a pawn of `color` on `sq` would push to
+fn stop_square(color: Color, sq: Square) -> Option<Square> {
+    sq.offset(0, if color == Color::White { 1 } else { -1 })
+}
+
+/// The pawn features of both sides, with the score they add up to.
+///
+/// Features are bitboards indexed by color; query them with `squares` or
+/// `features_on`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PawnReport {
+    squares: [[Bitboard; 7]; 2],
+    /// Terms that depend on the pawns alone, from White's point of view;
+    /// this is what the pawn hash table caches
+    pub structure: Score,
+    /// Passed-pawn terms that also depend on the pieces: blocked stop
+    /// squares and king distance
+    pub passed_extras: Score,
+}
+
+impl PawnReport {
+    /// The pawns of `color` that have `feature`
+    pub fn squares(&self, color: Color, feature: PawnFeature) -> Bitboard {
+        self.squares[color as usize][feature as usize]
+    }
+
+    /// The features of the pawn on `sq`, whichever its color; empty if there is none
+    pub fn features_on(&self, sq: Square) -> Vec<PawnFeature> {
+        PawnFeature::ALL
+            .into_iter()
+            .filter(|&f| {
+                self.squares(Color::White, f).contains(sq)
+                    || self.squares(Color::Black, f).contains(sq)
+            })
+            .collect()
+    }
+
+    /// The whole pawn term, from White's point of view
+    pub fn score(&self) -> Score {
+        self.structure + self.passed_extras
+    }
+}
+
+/// What the pawn hash table keeps per pawn structure
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct PawnEntry {
+    pub key: u64,
+    pub structure: Score,
+    /// Passed pawns by color, for the terms that cannot be cached
+    pub passed: [Bitboard; 2],
+}
+
+impl PawnEntry {
+    fn new(key: u64, report: &PawnReport) -> Self {
+        PawnEntry {
+            key,
+            structure: report.structure,
+            passed: [
+                report.squares(Color::White, PawnFeature::Passed),
+                report.squares(Color::Black, PawnFeature::Passed),
+            ],
+        }
+    }
+}
+
+/// Pawn hash table: pawn structure evaluations indexed by `Board::pawn_key`.
+///
+/// One entry per slot, always replaced. An empty slot holds key 0 and a zero
+/// score, which is also the right entry for a board without pawns.
+pub struct PawnTable {
+    entries: Vec<PawnEntry>,
+}
+
+impl Default for PawnTable {
+    fn default() -> Self {
+        PawnTable::new(1 << 14)
+    }
+}
+
+impl PawnTable {
+    /// A table with room for `entries` structures, rounded up to a power of two
+    pub fn new(entries: usize) -> Self {
+        PawnTable {
+            entries: vec![PawnEntry::default(); entries.max(1).next_power_of_two()],
+        }
+    }
+
+    /// The entry for the board's pawns, evaluating them on a miss
+    pub fn probe(&mut self, board: &Board) -> PawnEntry {
+        let mask = self.entries.len() - 1;
+        let slot = &mut self.entries[board.pawn_key as usize & mask];
+        if slot.key != board.pawn_key {
+            *slot = PawnEntry::new(board.pawn_key, &board.analyze_pawns());
+        }
+        *slot
+    }
+
+    /// Forget every entry
+    pub fn clear(&mut self) {
+        self.entries.fill(PawnEntry::default());
+    }
+}
+
+impl Board {
+    /// Every pawn feature of the position and the score it contributes
+    pub fn pawn_report(&self) -> PawnReport {
+        let mut report = self.analyze_pawns();
+        report.passed_extras = self.passed_pawn_extras(&[
+            report.squares(Color::White, PawnFeature::Passed),
+            report.squares(Color::Black, PawnFeature::Passed),
+        ]);
+        report
+    }
+
+    /// The pawn term without the hash table
+    pub(crate) fn pawn_entry(&self) -> PawnEntry {
+        PawnEntry::new(self.pawn_key, &self.analyze_pawns())
+    }
+
+    /// Everything that depends on the pawns alone
+    fn analyze_pawns(&self) -> PawnReport {
+        let mut report = PawnReport {
+            squares: [[Bitboard::EMPTY; 7]; 2],
+            structure: Score::ZERO,
+            passed_extras: Score::ZERO,
+        };
+
+        for color in [Color::White, Color::Black] {
+            let ours = self.pieces[color as usize][PieceType::Pawn as usize];
+            let theirs = self.pieces[color.opposite() as usize][PieceType::Pawn as usize];
+            let features = &mut report.squares[color as usize];
+            let mut score = Score::ZERO;
+
+            for sq in ours {
+                let rank = relative_rank(color, sq);
+                let ahead = ranks_ahead(color, sq);
+                let file = Bitboard::file(sq.file());
+                let adjacent = adjacent_files(sq);
+                let supporters = pawn_attacks(sq.bit(), color.opposite()) & ours;
+
+                let doubled = !(ahead & file & ours).is_empty();
+                let isolated = (adjacent & ours).is_empty();
+                let phalanx = !(adjacent & Bitboard::rank(sq.rank()) & ours).is_empty();
+                let supported = !supporters.is_empty();
+                let passed = !doubled && (ahead & (file | adjacent) & theirs).is_empty();
+                let backward = !isolated
+                    && (adjacent & !ahead & ours).is_empty()
+                    && stop_square(color, sq).is_some_and(|stop| {
+                        theirs.contains(stop)
+                            || !(pawn_attacks(stop.bit(), color) & theirs).is_empty()
+                    });
+
+                let flags = [
+                    doubled,
+                    isolated,
+                    backward,
+                    phalanx,
+                    supported,
+                    phalanx || supported,
+                    passed,
+                ];
+                for (feature, flag) in PawnFeature::ALL.into_iter().zip(flags) {
+                    if flag {
+                        features[feature as usize] |= sq.bit();
+                    }
+                }
+
+                if doubled {
+                    score += DOUBLED;
+                }
+                if isolated {
+                    score += ISOLATED;
+                }
+                if backward {
+                    score += BACKWARD;
+                }
+                if phalanx || supported {
+                    let v = CONNECTED[rank] * (1 + phalanx as i32)
+                        + SUPPORTER * supporters.popcount() as i32;
+                    score += Score::new(v, v * (rank as i32 - 2).max(0) / 4);
+                }
+                if passed {
+                    score += PASSED[rank];
+                }
+            }
+
+            if color == Color::White {
+                report.structure += score;
+            } else {
+                report.structure -= score;
+            }
+        }
+        report
+    }
+
+    /// Passed-pawn terms that depend on more than the pawns: a piece on the
+    /// stop square, and how close each king is to it. From White's point of view.
+    pub(crate) fn passed_pawn_extras(&self, passed: &[Bitboard; 2]) -> Score {
+        let mut total = Score::ZERO;
+        for color in [Color::White, Color::Black] {
+            let own_king = self.pieces[color as usize][PieceType::King as usize].lsb();
+            let their_king = self.pieces[color.opposite() as usize][PieceType::King as usize].lsb();
+            let mut score = Score::ZERO;
+
+            for sq in passed[color as usize] {
+                let weight = relative_rank(color, sq) as i32 - 2;
+                let Some(stop) = stop_square(color, sq) else {
+                    continue;
+                };
+                if weight <= 0 {
+                    continue;
+                }
+                if self.occupied.contains(stop) {
+                    score += PASSED_BLOCKED * weight;
+                }
+                let distance =
+                    |king: Option<Square>| king.map_or(0, |k| k.distance(stop).min(5) as i32);
+                let eg =
+                    PASSED_OWN_KING * distance(own_king) + PASSED_THEIR_KING * distance(their_king);
+                score += Score::new(0, eg * weight);
+            }
+
+            if color == Color::White {
+                total += score;
+            } else {
+                total -= score;
+            }
+        }
+        total
+    }
+}
@@ -0,0 +1,56 @@
+use crate::initialize_board::Board;
+use crate::move_list::MoveList;
+use crate::pseudo_legal_move_generation::Move;
+
+impl Board {
+    /// Count the leaf nodes of the legal move tree to the given depth,
+    /// making and unmaking every move including the last ply
+    pub fn perft(&self, depth: u32) -> u64 {
+        let mut board = *self;
+        board.perft_inner(depth, false)
+    }
+
+    /// Same count as `perft`, but the last ply is counted from the length
+    /// of the move list instead of making each move (bulk counting)
+    pub fn perft_bulk(&self, depth: u32) -> u64 {
+        let mut board = *self;
+        board.perft_inner(depth, true)
+    }
+
+    /// Perft split by root move, for finding which move a generator bug hides under
+    pub fn divide(&self, depth: u32) -> Vec<(Move, u64)> {
+        let mut board = *self;
+        let mut counts = Vec::new();
+        if depth == 0 {
+            return counts;
+        }
+        let mut moves = MoveList::new();
+        board.generate_legal_moves_into(&mut moves);
+        for m in moves {
+            let undo = board.make_move(&m);
+            counts.push((m, board.perft_inner(depth - 1, true)));
+            board.unmake_move(&m, &undo);
+        }
+        counts
+    }
+
+    fn perft_inner(&mut self, depth: u32, bulk: bool) -> u64 {
+        if depth == 0 {
+            return 1;
+        }
+
+        let mut moves = MoveList::new();
+        self.generate_legal_moves_into(&mut moves);
+        if bulk && depth == 1 {
+            return moves.len() as u64;
+        }
+
+        let mut nodes = 0;
+        for m in &moves {
+            let undo = self.make_move(m);
+            nodes += self.perft_inner(depth - 1, bulk);
+            self.unmake_move(m, &undo);
+        }
+        nodes
+    }
+}
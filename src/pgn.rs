@@ -0,0 +1,638 @@
+use std::error::Error;
+use std::fmt;
+use std::io::{self, BufRead};
+
+use crate::constants::*;
+use crate::fen::FenError;
+use crate::game::{Game, GameStatus};
+use crate::initialize_board::Board;
+use crate::pseudo_legal_move_generation::Move;
+use crate::san::SanError;
+
+/// The tags every PGN game should carry, in the order they are written
+pub const SEVEN_TAG_ROSTER: [&str; 7] =
+    ["Event", "Site", "Date", "Round", "White", "Black", "Result"];
+
+/// Outcome written at the end of the movetext and in the `Result` tag
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GameResult {
+    WhiteWins,
+    BlackWins,
+    Draw,
+    /// "*": still going, abandoned or unknown
+    #[default]
+    Unknown,
+}
+
+impl GameResult {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            GameResult::WhiteWins => "1-0",
+            GameResult::BlackWins => "0-1",
+            GameResult::Draw => "1/2-1/2",
+            GameResult::Unknown => "*",
+        }
+    }
+
+    /// Parse "1-0", "0-1", "1/2-1/2" or "*"
+    pub fn from_pgn(s: &str) -> Option<GameResult> {
+        match s {
+            "1-0" => Some(GameResult::WhiteWins),
+            "0-1" => Some(GameResult::BlackWins),
+            "1/2-1/2" => Some(GameResult::Draw),
+            "*" => Some(GameResult::Unknown),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for GameResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// A move of the movetext together with its annotations
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PgnMove {
+    pub mv: Move,
+    /// Comments written before the move; only used for the first move of a line
+    pub comments_before: Vec<String>,
+    /// Numeric annotation glyphs, e.g. 1 for "!" or "$1"
+    pub nags: Vec<u8>,
+    /// Comments written after the move, whitespace collapsed to single spaces
+    pub comments: Vec<String>,
+    /// Alternatives to this move, each played from the position before it
+    pub variations: Vec<Vec<PgnMove>>,
+}
+
+impl PgnMove {
+    pub fn new(mv: Move) -> Self {
+        PgnMove {
+            mv,
+            comments_before: Vec::new(),
+            nags: Vec::new(),
+            comments: Vec::new(),
+            variations: Vec::new(),
+        }
+    }
+}
+
+/// One game of a PGN file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PgnGame {
+    /// Tags in the order they were read
+    pub tags: Vec<(String, String)>,
+    /// The position the moves start from: the `FEN` tag, or the standard start
+    pub start: Board,
+    /// The main line
+    pub moves: Vec<PgnMove>,
+    pub result: GameResult,
+}
+
+impl PgnGame {
+    /// Value of the first tag with this name
+    pub fn tag(&self, name: &str) -> Option<&str> {
+        self.tags
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Moves of the main line, without annotations
+    pub fn mainline(&self) -> impl Iterator<Item = Move> + '_ {
+        self.moves.iter().map(|m| m.mv)
+    }
+
+    /// The main line played out as a `Game`
+    pub fn to_game(&self) -> Game {
+        let mut game = Game::from_board(self.start);
+        for m in self.mainline() {
+            game.make_move(&m);
+        }
+        game
+    }
+
+    /// A game with no tags and no moves from `start`
+    pub fn new(start: Board) -> Self {
+        PgnGame {
+            tags: Vec::new(),
+            start,
+            moves: Vec::new(),
+            result: GameResult::Unknown,
+        }
+    }
+
+    /// The moves of a `Game`, with the result taken from its status
+    pub fn from_game(game: &Game) -> Self {
+        let mut start = game.clone();
+        while start.take_back().is_some() {}
+
+        let mut pgn = PgnGame::new(*start.board());
+        pgn.moves = game.moves().map(|&m| PgnMove::new(m)).collect();
+        pgn.result = match game.status() {
+            GameStatus::Checkmate {
+                winner: Color::White,
+            } => GameResult::WhiteWins,
+            GameStatus::Checkmate {
+                winner: Color::Black,
+            } => GameResult::BlackWins,
+            status if status.is_game_over() => GameResult::Draw,
+            _ => GameResult::Unknown,
+        };
+        pgn
+    }
+
+    /// Set a tag, replacing an existing one of the same name
+    pub fn set_tag(&mut self, name: &str, value: &str) {
+        match self.tags.iter_mut().find(|(n, _)| n == name) {
+            Some((_, v)) => *v = value.to_string(),
+            None => self.tags.push((name.to_string(), value.to_string())),
+        }
+    }
+
+    /// The game in PGN export format: the Seven Tag Roster first ("?" for missing
+    /// values), `SetUp` and `FEN` when the start position is not the standard one,
+    /// the remaining tags, then the movetext wrapped at 80 columns
+    pub fn to_pgn(&self) -> String {
+        let mut out = String::new();
+        let mut tag = |name: &str, value: &str| {
+            let value = value.replace('\\', "\\\\").replace('"', "\\\"");
+            out.push_str(&format!("[{} \"{}\"]\n", name, value));
+        };
+
+        for name in SEVEN_TAG_ROSTER {
+            match name {
+                "Result" => tag(name, self.result.as_str()),
+                "Date" => tag(name, self.tag(name).unwrap_or("????.??.??")),
+                _ => tag(name, self.tag(name).unwrap_or("?")),
+            }
+        }
+        let fen = self.start.to_fen();
+        if fen != Board::new().to_fen() {
+            tag("SetUp", "1");
+            tag("FEN", &fen);
+        }
+        for (name, value) in &self.tags {
+            if !SEVEN_TAG_ROSTER.contains(&name.as_str()) && name != "SetUp" && name != "FEN" {
+                tag(name, value);
+            }
+        }
+        out.push('\n');
+
+        let mut tokens = Vec::new();
+        movetext_tokens(self.start, &self.moves, &mut tokens);
+        tokens.push(self.result.as_str().to_string());
+
+        let mut line_len = 0;
+        for token in tokens {
+            if line_len > 0 && line_len + 1 + token.len() > MAX_LINE {
+                out.push('\n');
+                line_len = 0;
+            }
+            if line_len > 0 {
+                out.push(' ');
+                line_len += 1;
+            }
+            out.push_str(&token);
+            line_len += token.len();
+        }
+        out.push('\n');
+        out
+    }
+}
+
+impl fmt::Display for PgnGame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_pgn())
+    }
+}
+
+/// Longest movetext line written
+const MAX_LINE: usize = 80;
+
+/// Push a comment word by word, so long comments can be wrapped too
+fn comment_tokens(comment: &str, tokens: &mut Vec<String>) {
+    let words: Vec<&str> = comment.split_whitespace().collect();
+    let last = words.len().saturating_sub(1);
+    for (i, word) in words.iter().enumerate() {
+        let mut token = String::new();
+        if i == 0 {
+            token.push('{');
+        }
+        token.push_str(word);
+        if i == last {
+            token.push('}');
+        }
+        tokens.push(token);
+    }
+    if words.is_empty() {
+        tokens.push("{}".to_string());
+    }
+}
+
+/// Movetext of one line starting from `board`. Black moves get an "N..." number
+/// at the start of a line and after a comment or a variation.
+fn movetext_tokens(mut board: Board, moves: &[PgnMove], tokens: &mut Vec<String>) {
+    let mut need_number = true;
+    for m in moves {
+        for comment in &m.comments_before {
+            comment_tokens(comment, tokens);
+        }
+        if board.turn == Color::White {
+            tokens.push(format!("{}.", board.full_moves));
+        } else if need_number {
+            tokens.push(format!("{}...", board.full_moves));
+        }
+        tokens.push(m.mv.to_san(&board));
+        need_number = false;
+
+        for nag in &m.nags {
+            tokens.push(format!("${}", nag));
+        }
+        for comment in &m.comments {
+            comment_tokens(comment, tokens);
+            need_number = true;
+        }
+        for variation in m.variations.iter().filter(|v| !v.is_empty()) {
+            let first = tokens.len();
+            movetext_tokens(board, variation, tokens);
+            tokens[first].insert(0, '(');
+            tokens.last_mut().unwrap().push(')');
+            need_number = true;
+        }
+
+        board.apply_move(&m.mv);
+    }
+}
+
+/// What went wrong while reading PGN
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PgnErrorKind {
+    /// Reading the input failed
+    Io(io::ErrorKind),
+    /// A character that cannot start or continue a token here
+    UnexpectedChar(char),
+    /// A tag pair not of the form [Name "value"]
+    MalformedTag,
+    /// A '{' comment or a tag value runs to the end of the input
+    Unterminated,
+    /// A '(' without its ')'
+    UnterminatedVariation,
+    /// A variation or annotation glyph with no move before it
+    NothingToAnnotate,
+    /// Not a NAG: "$" without a number below 256, or an unknown "!?" suffix
+    InvalidNag(String),
+    /// The `FEN` tag does not hold a valid position
+    InvalidFen(FenError),
+    /// A move that is not legal SAN in the position reached
+    InvalidMove(SanError),
+}
+
+/// PGN parse error, pointing at the line and column (both starting at 1) of the problem
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PgnError {
+    pub line: usize,
+    pub column: usize,
+    pub kind: PgnErrorKind,
+}
+
+impl fmt::Display for PgnError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid PGN at line {}, column {}: ",
+            self.line, self.column
+        )?;
+        match &self.kind {
+            PgnErrorKind::Io(kind) => write!(f, "read error ({})", kind),
+            PgnErrorKind::UnexpectedChar(c) => write!(f, "unexpected character '{}'", c),
+            PgnErrorKind::MalformedTag => write!(f, "malformed tag pair"),
+            PgnErrorKind::Unterminated => write!(f, "comment or string not closed"),
+            PgnErrorKind::UnterminatedVariation => write!(f, "variation not closed"),
+            PgnErrorKind::NothingToAnnotate => write!(f, "no move to annotate"),
+            PgnErrorKind::InvalidNag(nag) => write!(f, "invalid annotation glyph '{}'", nag),
+            PgnErrorKind::InvalidFen(e) => write!(f, "{}", e),
+            PgnErrorKind::InvalidMove(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl Error for PgnError {}
+
+/// Characters of SAN moves, move numbers and results
+fn is_symbol_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || "_+#=:-/".contains(c)
+}
+
+/// NAG for a move suffix such as "!?"
+fn suffix_nag(suffix: &str) -> Option<u8> {
+    Some(match suffix {
+        "!" => 1,
+        "?" => 2,
+        "!!" => 3,
+        "??" => 4,
+        "!?" => 5,
+        "?!" => 6,
+        _ => return None,
+    })
+}
+
+/// Reads games one at a time from any buffered reader, so files of any size
+/// can be processed game by game:
+///
+/// ```no_run
+/// use std::{fs::File, io::BufReader};
+/// use my_own_chess_engine::PgnReader;
+///
+/// let file = BufReader::new(File::open("games.pgn").unwrap());
+/// for game in PgnReader::new(file) {
+///     println!("{:?}", game.map(|g| g.result));
+/// }
+/// ```
+///
+/// After an error the reader skips ahead to the next game's tags.
+pub struct PgnReader<R> {
+    input: R,
+    /// Current line, newline included
+    line: Vec<char>,
+    /// Next character in `line`
+    pos: usize,
+    /// Number of the current line, starting at 1
+    line_no: usize,
+    eof: bool,
+}
+
+impl<R: BufRead> PgnReader<R> {
+    pub fn new(input: R) -> Self {
+        PgnReader {
+            input,
+            line: Vec::new(),
+            pos: 0,
+            line_no: 0,
+            eof: false,
+        }
+    }
+
+    fn error_at(&self, (line, column): (usize, usize), kind: PgnErrorKind) -> PgnError {
+        PgnError { line, column, kind }
+    }
+
+    /// Line and column of the next character
+    fn here(&self) -> (usize, usize) {
+        (self.line_no, self.pos + 1)
+    }
+
+    /// Load the next line once the current one is used up. Lines starting
+    /// with '%' are escapes for other programs and are skipped.
+    fn fill(&mut self) -> Result<(), PgnError> {
+        while self.pos >= self.line.len() && !self.eof {
+            let mut buf = String::new();
+            let read = self.input.read_line(&mut buf).map_err(|e| PgnError {
+                line: self.line_no + 1,
+                column: 1,
+                kind: PgnErrorKind::Io(e.kind()),
+            })?;
+            self.line_no += 1;
+            self.pos = 0;
+            if read == 0 {
+                self.eof = true;
+                self.line.clear();
+            } else if buf.starts_with('%') {
+                self.line.clear();
+            } else {
+                self.line = buf.chars().collect();
+            }
+        }
+        Ok(())
+    }
+
+    fn peek(&mut self) -> Result<Option<char>, PgnError> {
+        self.fill()?;
+        Ok(self.line.get(self.pos).copied())
+    }
+
+    fn bump(&mut self) -> Result<Option<char>, PgnError> {
+        let c = self.peek()?;
+        if c.is_some() {
+            self.pos += 1;
+        }
+        Ok(c)
+    }
+
+    fn skip_whitespace(&mut self) -> Result<(), PgnError> {
+        while self.peek()?.is_some_and(char::is_whitespace) {
+            self.pos += 1;
+        }
+        Ok(())
+    }
+
+    /// Consume characters while `keep` holds
+    fn take_while(&mut self, keep: impl Fn(char) -> bool) -> Result<String, PgnError> {
+        let mut s = String::new();
+        while let Some(c) = self.peek()? {
+            if !keep(c) {
+                break;
+            }
+            s.push(c);
+            self.pos += 1;
+        }
+        Ok(s)
+    }
+
+    /// After an error: drop input up to a '[' that starts a line after a blank line
+    fn skip_to_next_game(&mut self) {
+        let mut blank_seen = false;
+        loop {
+            self.pos = self.line.len();
+            if self.fill().is_err() || self.eof {
+                return;
+            }
+            if blank_seen && self.line.first() == Some(&'[') {
+                return;
+            }
+            blank_seen |= self.line.iter().all(|c| c.is_whitespace());
+        }
+    }
+
+    fn read_game(&mut self) -> Result<Option<PgnGame>, PgnError> {
+        self.skip_whitespace()?;
+        if self.peek()?.is_none() {
+            return Ok(None);
+        }
+
+        let mut tags = Vec::new();
+        let mut start = Board::new();
+        while self.peek()? == Some('[') {
+            let at = self.here();
+            let (name, value) = self.read_tag()?;
+            if name == "FEN" {
+                start = Board::from_fen(&value)
+                    .map_err(|e| self.error_at(at, PgnErrorKind::InvalidFen(e)))?;
+            }
+            tags.push((name, value));
+            self.skip_whitespace()?;
+        }
+
+        let (moves, result) = self.read_line(start, false)?;
+        let result = result
+            .or_else(|| {
+                tags.iter()
+                    .find(|(n, _)| n == "Result")
+                    .and_then(|(_, v)| GameResult::from_pgn(v))
+            })
+            .unwrap_or_default();
+        Ok(Some(PgnGame {
+            tags,
+            start,
+            moves,
+            result,
+        }))
+    }
+
+    /// [Name "value"], with \" and \\ escapes in the value
+    fn read_tag(&mut self) -> Result<(String, String), PgnError> {
+        let malformed = |r: &Self| r.error_at(r.here(), PgnErrorKind::MalformedTag);
+        self.bump()?; // '['
+        self.skip_whitespace()?;
+        let name = self.take_while(is_symbol_char)?;
+        self.skip_whitespace()?;
+        if name.is_empty() || self.bump()? != Some('"') {
+            return Err(malformed(self));
+        }
+        let mut value = String::new();
+        loop {
+            let at = self.here();
+            match self.bump()? {
+                Some('"') => break,
+                Some('\\') => match self.bump()? {
+                    Some(c @ ('"' | '\\')) => value.push(c),
+                    _ => return Err(self.error_at(at, PgnErrorKind::MalformedTag)),
+                },
+                Some('\n') | None => return Err(self.error_at(at, PgnErrorKind::Unterminated)),
+                Some(c) => value.push(c),
+            }
+        }
+        self.skip_whitespace()?;
+        if self.bump()? != Some(']') {
+            return Err(malformed(self));
+        }
+        Ok((name, value))
+    }
+
+    /// Read a line of moves starting from `board` until its end: the result or the
+    /// next game for the main line, the closing ')' for a variation
+    fn read_line(
+        &mut self,
+        mut board: Board,
+        variation: bool,
+    ) -> Result<(Vec<PgnMove>, Option<GameResult>), PgnError> {
+        let mut moves: Vec<PgnMove> = Vec::new();
+        let mut pending_comments = Vec::new();
+        // The position before the last move, where its variations start
+        let mut before = board;
+
+        loop {
+            self.skip_whitespace()?;
+            let at = self.here();
+            let Some(c) = self.peek()? else {
+                if variation {
+                    return Err(self.error_at(at, PgnErrorKind::UnterminatedVariation));
+                }
+                return Ok((moves, None));
+            };
+
+            match c {
+                '{' | ';' => {
+                    self.pos += 1;
+                    let comment = if c == '{' {
+                        let text = self.take_while(|c| c != '}')?;
+                        if self.bump()? != Some('}') {
+                            return Err(self.error_at(at, PgnErrorKind::Unterminated));
+                        }
+                        text
+                    } else {
+                        self.take_while(|c| c != '\n')?
+                    };
+                    let comment = comment.split_whitespace().collect::<Vec<_>>().join(" ");
+                    match moves.last_mut() {
+                        Some(last) => last.comments.push(comment),
+                        None => pending_comments.push(comment),
+                    }
+                }
+                '(' => {
+                    self.pos += 1;
+                    let Some(last) = moves.last_mut() else {
+                        return Err(self.error_at(at, PgnErrorKind::NothingToAnnotate));
+                    };
+                    let (line, _) = self.read_line(before, true)?;
+                    last.variations.push(line);
+                }
+                ')' if variation => {
+                    self.pos += 1;
+                    return Ok((moves, None));
+                }
+                '$' | '!' | '?' => {
+                    let text = if c == '$' {
+                        self.pos += 1;
+                        let digits = self.take_while(|c| c.is_ascii_digit())?;
+                        format!("${}", digits)
+                    } else {
+                        self.take_while(|c| c == '!' || c == '?')?
+                    };
+                    let nag = if c == '$' {
+                        text[1..].parse().ok()
+                    } else {
+                        suffix_nag(&text)
+                    };
+                    let nag =
+                        nag.ok_or_else(|| self.error_at(at, PgnErrorKind::InvalidNag(text)))?;
+                    let last = moves
+                        .last_mut()
+                        .ok_or_else(|| self.error_at(at, PgnErrorKind::NothingToAnnotate))?;
+                    last.nags.push(nag);
+                }
+                // A tag at the start of a line: the game ended without a result
+                '[' if !variation && at.1 == 1 => return Ok((moves, None)),
+                '*' if !variation => {
+                    self.pos += 1;
+                    return Ok((moves, Some(GameResult::Unknown)));
+                }
+                c if is_symbol_char(c) => {
+                    let token = self.take_while(is_symbol_char)?;
+                    if let Some(result) = GameResult::from_pgn(&token).filter(|_| !variation) {
+                        return Ok((moves, Some(result)));
+                    }
+                    if token.chars().all(|c| c.is_ascii_digit()) {
+                        // Move number; its dots are optional
+                        self.take_while(|c| c == '.')?;
+                        continue;
+                    }
+                    let mv = board
+                        .parse_san(&token)
+                        .map_err(|e| self.error_at(at, PgnErrorKind::InvalidMove(e)))?;
+                    let mut pgn_move = PgnMove::new(mv);
+                    pgn_move.comments_before = std::mem::take(&mut pending_comments);
+                    moves.push(pgn_move);
+                    before = board;
+                    board.apply_move(&mv);
+                }
+                c => return Err(self.error_at(at, PgnErrorKind::UnexpectedChar(c))),
+            }
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for PgnReader<R> {
+    type Item = Result<PgnGame, PgnError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.read_game() {
+            Ok(game) => game.map(Ok),
+            Err(e) => {
+                self.skip_to_next_game();
+                Some(Err(e))
+            }
+        }
+    }
+}
@@ -0,0 +1,211 @@
+use crate::constants::*;
+use crate::eval::Score;
+use crate::initialize_board::Board;
+use crate::pawn_directions::pawn_attacks;
+use crate::pawn_structure::{adjacent_files, ranks_ahead, relative_rank};
+use crate::pseudo_legal_move_generation::piece_attacks;
+use crate::square::*;
+
+/// Mobility by piece type (knight, bishop, rook, queen): the number of squares
+/// that counts as average, and the value of each square above or below it
+const MOBILITY: [(i32, Score); 4] = [
+    (4, Score::new(6, 6)),
+    (6, Score::new(5, 6)),
+    (6, Score::new(3, 6)),
+    (12, Score::new(1, 3)),
+];
+const KNIGHT_OUTPOST: Score = Score::new(32, 18);
+const BISHOP_OUTPOST: Score = Score::new(18, 8);
+const ROOK_OPEN_FILE: Score = Score::new(40, 15);
+const ROOK_HALF_OPEN_FILE: Score = Score::new(18, 8);
+const ROOK_ON_SEVENTH: Score = Score::new(20, 35);
+const BISHOP_PAIR: Score = Score::new(30, 55);
+const TRAPPED_ROOK: Score = Score::new(-50, -10);
+const TRAPPED_BISHOP: Score = Score::new(-80, -80);
+/// Per pawn of our own on the bishop's square color, multiplied up by
+/// blocked centre pawns
+const BAD_BISHOP: Score = Score::new(-3, -7);
+
+/// The piece-activity terms of one side, each from that side's point of view,
+/// as returned by `Board::piece_activity`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct PieceActivity {
+    /// Knight, bishop, rook and queen mobility over the safe squares
+    pub mobility: [Score; 4],
+    /// Knights and bishops on squares no enemy pawn can ever attack
+    pub outposts: Score,
+    /// Rooks on files without pawns
+    pub open_files: Score,
+    /// Rooks on files without pawns of their own
+    pub half_open_files: Score,
+    /// Rooks on the 7th rank with the enemy king or pawns still on the 8th or 7th
+    pub seventh_rank: Score,
+    pub bishop_pair: Score,
+    /// Rooks shut in by their own king, and bishops caught behind a pawn on a7/h7
+    pub trapped: Score,
+    /// Bishops hemmed in by pawns of their own on their square color
+    pub bad_bishops: Score,
+}
+
+impl PieceActivity {
+    /// Every term added up
+    pub fn total(&self) -> Score {
+        self.mobility.iter().fold(Score::ZERO, |sum, &s| sum + s)
+            + self.outposts
+            + self.open_files
+            + self.half_open_files
+            + self.seventh_rank
+            + self.bishop_pair
+            + self.trapped
+            + self.bad_bishops
+    }
+}
+
+impl Board {
+    /// Mobility, outposts, rook files, bishop pair, trapped pieces and bad
+    /// bishops of `color`, each term kept separate
+    pub fn piece_activity(&self, color: Color) -> PieceActivity {
+        let them = color.opposite();
+        let ours = &self.pieces[color as usize];
+        let theirs = &self.pieces[them as usize];
+        let our_pawns = ours[PieceType::Pawn as usize];
+        let their_pawns = theirs[PieceType::Pawn as usize];
+        let mut activity = PieceActivity::default();
+
+        // Squares neither guarded by their pawns nor taken by our pawns and king
+        let safe = !pawn_attacks(their_pawns, them) & !(our_pawns | ours[PieceType::King as usize]);
+        let pawn_guarded = pawn_attacks(our_pawns, color);
+
+        for (i, piece_type) in [
+            PieceType::Knight,
+            PieceType::Bishop,
+            PieceType::Rook,
+            PieceType::Queen,
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            let (average, per_square) = MOBILITY[i];
+            for sq in ours[piece_type as usize] {
+                let squares =
+                    (piece_attacks(piece_type, color, sq, self.occupied) & safe).popcount() as i32;
+                activity.mobility[i] += per_square * (squares - average);
+
+                match piece_type {
+                    PieceType::Knight | PieceType::Bishop => {
+                        let rank = relative_rank(color, sq);
+                        if (3..=5).contains(&rank)
+                            && pawn_guarded.contains(sq)
+                            && (ranks_ahead(color, sq) & adjacent_files(sq) & their_pawns)
+                                .is_empty()
+                        {
+                            activity.outposts += if piece_type == PieceType::Knight {
+                                KNIGHT_OUTPOST
+                            } else {
+                                BISHOP_OUTPOST
+                            };
+                        }
+                    }
+                    PieceType::Rook => {
+                        let file = Bitboard::file(sq.file());
+                        if (file & our_pawns).is_empty() {
+                            if (file & their_pawns).is_empty() {
+                                activity.open_files += ROOK_OPEN_FILE;
+                            } else {
+                                activity.half_open_files += ROOK_HALF_OPEN_FILE;
+                            }
+                        }
+                        if relative_rank(color, sq) == 6 {
+                            let their_king = theirs[PieceType::King as usize];
+                            let seventh = Bitboard::rank(sq.rank());
+                            if !(their_king & ranks_ahead(color, sq)).is_empty()
+                                || !(seventh & their_pawns).is_empty()
+                            {
+                                activity.seventh_rank += ROOK_ON_SEVENTH;
+                            }
+                        }
+                        if squares <= 3 && self.rook_shut_in(color, sq) {
+                            activity.trapped += TRAPPED_ROOK;
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        let bishops = ours[PieceType::Bishop as usize];
+        if !(bishops & LIGHT_SQUARES).is_empty() && !(bishops & DARK_SQUARES).is_empty() {
+            activity.bishop_pair += BISHOP_PAIR;
+        }
+
+        // Our pawns with something standing right in front of them
+        let blocked = match color {
+            Color::White => our_pawns & self.occupied.south(),
+            Color::Black => our_pawns & self.occupied.north(),
+        };
+        let center = Bitboard::file(File::C)
+            | Bitboard::file(File::D)
+            | Bitboard::file(File::E)
+            | Bitboard::file(File::F);
+        let blocked_center = (blocked & center).popcount() as i32;
+        for sq in bishops {
+            let color_squares = if LIGHT_SQUARES.contains(sq) {
+                LIGHT_SQUARES
+            } else {
+                DARK_SQUARES
+            };
+            let same_color = (our_pawns & color_squares).popcount() as i32;
+            activity.bad_bishops += BAD_BISHOP * (same_color * (1 + blocked_center));
+
+            if self.bishop_trapped(color, sq) {
+                activity.trapped += TRAPPED_BISHOP;
+            }
+        }
+
+        activity
+    }
+
+    /// A rook on our back rank between our king and the corner, where
+    /// castling can no longer free it
+    fn rook_shut_in(&self, color: Color, rook: Square) -> bool {
+        let Some(king) = self.pieces[color as usize][PieceType::King as usize].lsb() else {
+            return false;
+        };
+        if relative_rank(color, rook) != 0 || king.rank() != rook.rank() {
+            return false;
+        }
+        let (kingside, queenside) = match color {
+            Color::White => (WHITE_KINGSIDE, WHITE_QUEENSIDE),
+            Color::Black => (BLACK_KINGSIDE, BLACK_QUEENSIDE),
+        };
+        let (king_file, rook_file) = (king.file().index(), rook.file().index());
+        if king_file >= 4 && rook_file > king_file {
+            self.castling_rights & kingside == 0
+        } else if king_file < 4 && rook_file < king_file {
+            self.castling_rights & queenside == 0
+        } else {
+            false
+        }
+    }
+
+    /// A bishop on a7 or h7 (a2 or h2 for Black) whose way out is blocked by
+    /// an enemy pawn on b6 or g6 (b3 or g3)
+    fn bishop_trapped(&self, color: Color, bishop: Square) -> bool {
+        let their_pawns = self.pieces[color.opposite() as usize][PieceType::Pawn as usize];
+        let (corner_rank, pawn_dy) = match color {
+            Color::White => (6, -1),
+            Color::Black => (1, 1),
+        };
+        if bishop.rank().index() != corner_rank {
+            return false;
+        }
+        let dx = match bishop.file() {
+            File::A => 1,
+            File::H => -1,
+            _ => return false,
+        };
+        bishop
+            .offset(dx, pawn_dy)
+            .is_some_and(|sq| their_pawns.contains(sq))
+    }
+}
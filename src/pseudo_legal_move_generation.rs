@@ -1,99 +1,279 @@
+use std::fmt;
+
 use crate::attack::*;
 use crate::constants::*;
 use crate::initialize_board::*;
+use crate::magic::*;
+use crate::move_list::MoveList;
 use crate::pawn_directions::*;
+use crate::square::*;
 use crate::utils::*;
 
-#[derive(Debug, Clone, Copy)]
-pub struct Move {
+/// A move packed into 16 bits:
+///
+/// ```text
+/// bits  0-5   from square
+/// bits  6-11  to square
+/// bits 12-15  flags (see the associated constants)
+/// ```
+///
+/// The flags say what kind of move it is, so making, unmaking and printing a
+/// move never has to look at the board to find out.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Move(u16);
+
+/// The move as plain coordinates, without flags
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnpackedMove {
     pub from: Square,
     pub to: Square,
     pub promotion: Option<PieceType>, // For pawns
 }
+
 impl Move {
-    pub fn moving_piece(&self, board: &Board) -> Option<PieceType> {
-        let color = board.turn;
-        for pt in 0..6 {
-            if get_bit(board.pieces[color as usize][pt], self.from) {
-                return PieceType::from_usize(pt); // you'll need this helper
-            }
+    pub const QUIET: u8 = 0b0000;
+    pub const DOUBLE_PUSH: u8 = 0b0001;
+    pub const KING_CASTLE: u8 = 0b0010;
+    pub const QUEEN_CASTLE: u8 = 0b0011;
+    pub const CAPTURE: u8 = 0b0100;
+    pub const EN_PASSANT: u8 = 0b0101;
+    /// Set for all promotions; the low two bits pick the piece
+    pub const PROMOTION: u8 = 0b1000;
+    pub const KNIGHT_PROMOTION: u8 = 0b1000;
+    pub const BISHOP_PROMOTION: u8 = 0b1001;
+    pub const ROOK_PROMOTION: u8 = 0b1010;
+    pub const QUEEN_PROMOTION: u8 = 0b1011;
+
+    pub const fn new(from: Square, to: Square, flags: u8) -> Self {
+        Move(from.to_u8() as u16 | (to.to_u8() as u16) << 6 | (flags as u16) << 12)
+    }
+
+    /// A promotion to `piece` (knight, bishop, rook or queen), capturing or not
+    pub fn new_promotion(from: Square, to: Square, piece: PieceType, capture: bool) -> Self {
+        debug_assert!(matches!(
+            piece,
+            PieceType::Knight | PieceType::Bishop | PieceType::Rook | PieceType::Queen
+        ));
+        let mut flags = Self::PROMOTION | (piece as u8 - PieceType::Knight as u8);
+        if capture {
+            flags |= Self::CAPTURE;
         }
-        None
+        Self::new(from, to, flags)
     }
 
-    pub fn captured_piece(&self, board: &Board) -> Option<PieceType> {
-        let opp_color = if board.turn == Color::White {
-            Color::Black
-        } else {
+    /// Rebuild a move from `raw()`, e.g. when reading it back from a hash table
+    pub const fn from_raw(raw: u16) -> Self {
+        Move(raw)
+    }
+
+    pub const fn raw(self) -> u16 {
+        self.0
+    }
+
+    pub const fn from(self) -> Square {
+        Square::from_index((self.0 & 0x3F) as u8)
+    }
+
+    pub const fn to(self) -> Square {
+        Square::from_index((self.0 >> 6 & 0x3F) as u8)
+    }
+
+    pub const fn flags(self) -> u8 {
+        (self.0 >> 12) as u8
+    }
+
+    pub fn promotion(self) -> Option<PieceType> {
+        if !self.is_promotion() {
+            return None;
+        }
+        Some(match self.flags() & 0b11 {
+            0 => PieceType::Knight,
+            1 => PieceType::Bishop,
+            2 => PieceType::Rook,
+            _ => PieceType::Queen,
+        })
+    }
+
+    pub const fn is_promotion(self) -> bool {
+        self.flags() & Self::PROMOTION != 0
+    }
+
+    /// True for every capture, en passant and capturing promotions included
+    pub const fn is_capture(self) -> bool {
+        self.flags() & Self::CAPTURE != 0
+    }
+
+    pub const fn is_en_passant(self) -> bool {
+        self.flags() == Self::EN_PASSANT
+    }
+
+    pub const fn is_double_push(self) -> bool {
+        self.flags() == Self::DOUBLE_PUSH
+    }
+
+    pub const fn is_castling(self) -> bool {
+        matches!(self.flags(), Self::KING_CASTLE | Self::QUEEN_CASTLE)
+    }
+
+    /// King and rook squares of a castling move (None for any other move)
+    pub fn castling_path(self) -> Option<&'static CastlingPath> {
+        let side = match self.flags() {
+            Self::KING_CASTLE => 0,
+            Self::QUEEN_CASTLE => 1,
+            _ => return None,
+        };
+        let color = if self.from().rank() == Rank::ONE {
             Color::White
+        } else {
+            Color::Black
         };
-        for pt in 0..6 {
-            if get_bit(board.pieces[opp_color as usize][pt], self.to) {
-                // Special case: en passant capture
-                if board.en_passant == Some(self.to)
-                    && self.moving_piece(board) == Some(PieceType::Pawn)
-                {
-                    return Some(PieceType::Pawn);
-                }
-                return PieceType::from_usize(pt);
-            }
+        Some(&CASTLING_PATHS[color as usize][side])
+    }
+
+    /// Neither a capture nor a promotion
+    pub const fn is_quiet(self) -> bool {
+        self.flags() & (Self::CAPTURE | Self::PROMOTION) == 0
+    }
+
+    /// The piece of the side to move standing on the from square
+    pub fn moving_piece(&self, board: &Board) -> Option<PieceType> {
+        board
+            .piece_on(self.from())
+            .filter(|p| p.color == board.turn)
+            .map(|p| p.piece_type)
+    }
+
+    /// The piece this move takes, if any; `board` is the position before the move
+    pub fn captured_piece(&self, board: &Board) -> Option<PieceType> {
+        if !self.is_capture() {
+            return None;
         }
-        None
+        // En passant: the target square is empty, the pawn stands behind it
+        if self.is_en_passant() {
+            return Some(PieceType::Pawn);
+        }
+        board
+            .piece_on(self.to())
+            .filter(|p| p.color != board.turn)
+            .map(|p| p.piece_type)
     }
+
     pub fn to_long_algebraic(&self, board: &Board) -> String {
-        if self.moving_piece(board).is_none() {
+        let Some(piece) = self.moving_piece(board) else {
             return String::new();
-        }
-        let piece_char = match self.moving_piece(board).unwrap() {
-            PieceType::Pawn => "",
-            PieceType::Knight => "N",
-            PieceType::Bishop => "B",
-            PieceType::Rook => "R",
-            PieceType::Queen => "Q",
-            PieceType::King => "K",
         };
-
-        let from_str = square_to_algebraic(self.from);
-        let to_str = square_to_algebraic(self.to);
-        let promo_str = self
-            .promotion
-            .map(|p| match p {
-                PieceType::Queen => "q",
-                PieceType::Rook => "r",
-                PieceType::Bishop => "b",
-                PieceType::Knight => "n",
-                _ => "",
-            })
-            .unwrap_or("");
-
-        let capture = if self.captured_piece(board).is_some() {
-            "x"
-        } else {
-            ""
+        let piece_str = match piece {
+            PieceType::Pawn => String::new(),
+            other => other.to_char().to_string(),
         };
+        let capture = if self.is_capture() { "x" } else { "" };
+        let promo_str = self
+            .promotion()
+            .map(|p| p.to_char().to_ascii_lowercase().to_string())
+            .unwrap_or_default();
 
         format!(
             "{}{}{}{}{}",
-            piece_char, from_str, capture, to_str, promo_str
+            piece_str,
+            square_to_algebraic(self.from()),
+            capture,
+            square_to_algebraic(self.to()),
+            promo_str
         )
     }
 }
 
-fn square_to_algebraic(sq: Square) -> String {
-    let file = (sq % 8) as u8 + b'a';
-    let rank = (sq / 8) + 1;
-    format!("{}{}", file as char, rank)
+impl fmt::Debug for Move {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Move")
+            .field("from", &square_to_algebraic(self.from()))
+            .field("to", &square_to_algebraic(self.to()))
+            .field("flags", &format_args!("{:#06b}", self.flags()))
+            .finish()
+    }
+}
+
+/// Only the promotion can be recovered from the coordinates alone; capture,
+/// double push, castling and en passant flags need the position, so prefer
+/// `Board::encode_move` (or matching against the legal moves) wherever a board is at hand.
+/// A capture or special move converted this way is not fit for `make_move`,
+/// which debug builds catch with an assertion.
+impl From<UnpackedMove> for Move {
+    fn from(m: UnpackedMove) -> Self {
+        match m.promotion {
+            Some(piece) => Move::new_promotion(m.from, m.to, piece, false),
+            None => Move::new(m.from, m.to, Move::QUIET),
+        }
+    }
+}
+
+impl From<Move> for UnpackedMove {
+    fn from(m: Move) -> Self {
+        UnpackedMove {
+            from: m.from(),
+            to: m.to(),
+            promotion: m.promotion(),
+        }
+    }
+}
+
+/// Helper: convert a square to algebraic notation like "e4"; same as `to_string`
+pub fn square_to_algebraic(sq: Square) -> String {
+    sq.to_string()
 }
+
 impl Board {
+    /// Turn plain coordinates into a fully flagged move for this position.
+    /// The move is not checked for legality.
+    pub fn encode_move(&self, m: UnpackedMove) -> Move {
+        let capture = get_bit(self.all_pieces(self.turn.opposite()), m.to);
+        if let Some(piece) = m.promotion {
+            return Move::new_promotion(m.from, m.to, piece, capture);
+        }
+        let flags = match Move::new(m.from, m.to, Move::QUIET).moving_piece(self) {
+            Some(PieceType::Pawn)
+                if self.en_passant == Some(m.to) && m.from.file() != m.to.file() =>
+            {
+                Move::EN_PASSANT
+            }
+            Some(PieceType::Pawn) if m.from.rank().to_u8().abs_diff(m.to.rank().to_u8()) == 2 => {
+                Move::DOUBLE_PUSH
+            }
+            Some(PieceType::King) if m.from.offset(2, 0) == Some(m.to) => Move::KING_CASTLE,
+            Some(PieceType::King) if m.from.offset(-2, 0) == Some(m.to) => Move::QUEEN_CASTLE,
+            _ if capture => Move::CAPTURE,
+            _ => Move::QUIET,
+        };
+        Move::new(m.from, m.to, flags)
+    }
+
+    /// Pseudo-legal moves: they may leave the own king in check.
+    /// Castling is the exception and is only generated when fully legal.
     pub fn generate_pseudo_moves(&self) -> Vec<Move> {
-        let mut moves = Vec::new();
-        let color = self.turn;
+        let mut moves = MoveList::new();
+        self.generate_pseudo_moves_into(&mut moves);
+        moves.to_vec()
+    }
 
-        let opp_color = if color == Color::White {
-            Color::Black
-        } else {
-            Color::White
-        };
+    /// Like `generate_pseudo_moves`, but appends to a caller-provided list
+    pub fn generate_pseudo_moves_into(&self, moves: &mut MoveList) {
+        let own_pieces = self.all_pieces(self.turn);
+        self.generate_piece_moves(moves, true, true, Bitboard::FULL);
+        self.generate_king_moves(moves, !own_pieces);
+        self.generate_castling_moves(moves);
+    }
+
+    /// Pawn, knight, bishop, rook and queen moves that land on `targets`.
+    /// `noisy` adds captures (en passant included) and promotions, `quiet` every other move.
+    pub(crate) fn generate_piece_moves(
+        &self,
+        moves: &mut MoveList,
+        noisy: bool,
+        quiet: bool,
+        targets: Bitboard,
+    ) {
+        let color = self.turn;
+        let opp_color = color.opposite();
 
         let own_pieces = self.all_pieces(color);
         let opp_pieces = self.all_pieces(opp_color);
@@ -103,13 +283,13 @@ impl Board {
         // helper to add normal moves
         macro_rules! add_moves {
             ($from:expr, $targets:expr) => {
-                let mut targets = $targets;
-                while let Some(to) = pop_lsb(&mut targets) {
-                    moves.push(Move {
-                        from: $from,
-                        to,
-                        promotion: None,
-                    });
+                for to in $targets {
+                    let flags = if get_bit(opp_pieces, to) {
+                        Move::CAPTURE
+                    } else {
+                        Move::QUIET
+                    };
+                    moves.push(Move::new($from, to, flags));
                 }
             };
         }
@@ -117,6 +297,12 @@ impl Board {
         // Pawns
         let pawns = self.pieces[color as usize][PieceType::Pawn as usize];
         let direction: i8 = if color == Color::White { 8 } else { -8 };
+        // Target of a pawn on `from` going `dx` files sideways and `ranks` ranks forward.
+        // The bitboard shifts already dropped every move that would leave the board.
+        let forward = |from: Square, dx: i8, ranks: i8| {
+            from.offset(dx, ranks * direction.signum())
+                .expect("pawn move off the board")
+        };
 
         let promotion_rank = if color == Color::White {
             RANK_8
@@ -131,402 +317,214 @@ impl Board {
 
         // single pushes
         let single_push = shift(pawns, direction) & empty;
-        let mut push_from = shift(single_push, -direction); // back to origin
-
         // Promotion via push
-        let promo_pushes = single_push & promotion_rank;
-        let mut promo_from = shift(promo_pushes, -direction);
-
-        while let Some(from) = pop_lsb(&mut promo_from) {
-            let to = (from as i8 + direction) as Square;
-            for &promo in &[
-                PieceType::Queen,
-                PieceType::Rook,
-                PieceType::Bishop,
-                PieceType::Knight,
-            ] {
-                moves.push(Move {
-                    from,
-                    to,
-                    promotion: Some(promo),
-                });
+        if noisy {
+            let promo_pushes = single_push & promotion_rank & targets;
+            for from in shift(promo_pushes, -direction) {
+                let to = forward(from, 0, 1);
+                for &promo in &[
+                    PieceType::Queen,
+                    PieceType::Rook,
+                    PieceType::Bishop,
+                    PieceType::Knight,
+                ] {
+                    moves.push(Move::new_promotion(from, to, promo, false));
+                }
             }
         }
-        // Normal single pushes (non-promotion)
-        let normal_pushes = single_push & !promotion_rank;
-        push_from = shift(normal_pushes, -direction);
-        while let Some(from) = pop_lsb(&mut push_from) {
-            let to = (from as i8 + direction) as Square;
-            moves.push(Move {
-                from,
-                to,
-                promotion: None,
-            });
-        }
+        if quiet {
+            // Normal single pushes (non-promotion)
+            let normal_pushes = single_push & !promotion_rank & targets;
+            // back to origin
+            for from in shift(normal_pushes, -direction) {
+                let to = forward(from, 0, 1);
+                moves.push(Move::new(from, to, Move::QUIET));
+            }
 
-        // Double pushes
-        let double_possible = shift(single_push, direction) & empty & double_push_rank;
-        let mut double_from = shift(double_possible, -direction * 2);
-        while let Some(from) = pop_lsb(&mut double_from) {
-            let to = (from as i8 + direction * 2) as Square;
-            moves.push(Move {
-                from,
-                to,
-                promotion: None,
-            });
+            // Double pushes
+            let double_possible =
+                shift(single_push, direction) & empty & double_push_rank & targets;
+            for from in shift(double_possible, -direction * 2) {
+                let to = forward(from, 0, 2);
+                moves.push(Move::new(from, to, Move::DOUBLE_PUSH));
+            }
         }
         // =====================
         // Pawn Captures
         // =====================
 
-        // Capture targets include en passant square
-        let capture_targets = opp_pieces | self.en_passant.map_or(0, |sq| 1u64 << sq);
-
-        // ---------- LEFT DIAGONAL CAPTURES ----------
-        let left_attacks = if color == Color::White {
-            (pawns << 7) & NOT_A_FILE
-        } else {
-            (pawns >> 9) & NOT_H_FILE
-        };
+        if noisy {
+            // Capture targets include en passant square. An en passant capture also
+            // counts as hitting `targets` when the pawn it removes stands on one.
+            let ep_target = self
+                .en_passant
+                .filter(|&ep| {
+                    let captured = ep
+                        .offset(0, -direction.signum())
+                        .expect("bad en passant square");
+                    get_bit(targets, ep) || get_bit(targets, captured)
+                })
+                .map_or(Bitboard::EMPTY, Square::bit);
+            let capture_targets = (opp_pieces & targets) | ep_target;
+            let capture_flags = |to: Square| {
+                if self.en_passant == Some(to) {
+                    Move::EN_PASSANT
+                } else {
+                    Move::CAPTURE
+                }
+            };
 
-        let left_caps = left_attacks & capture_targets;
+            // ---------- LEFT DIAGONAL CAPTURES ----------
+            let left_attacks = if color == Color::White {
+                shift_north_west(pawns)
+            } else {
+                shift_south_west(pawns)
+            };
 
-        // Recover FROM square
-        let left_from_shift = if color == Color::White { -7 } else { 9 };
-        // Compute TO square
-        let left_delta = if color == Color::White { 7 } else { -9 };
+            let left_caps = left_attacks & capture_targets;
 
-        let mut left_from = shift(left_caps, left_from_shift);
+            // Recover FROM square
+            let left_from_shift = if color == Color::White { -7 } else { 9 };
 
-        while let Some(from) = pop_lsb(&mut left_from) {
-            let to = (from as i16 + left_delta) as Square;
+            for from in shift(left_caps, left_from_shift) {
+                let to = forward(from, -1, 1);
 
-            if get_bit(promotion_rank, to) {
-                for &promo in &[
-                    PieceType::Queen,
-                    PieceType::Rook,
-                    PieceType::Bishop,
-                    PieceType::Knight,
-                ] {
-                    moves.push(Move {
-                        from,
-                        to,
-                        promotion: Some(promo),
-                    });
+                if get_bit(promotion_rank, to) {
+                    for &promo in &[
+                        PieceType::Queen,
+                        PieceType::Rook,
+                        PieceType::Bishop,
+                        PieceType::Knight,
+                    ] {
+                        moves.push(Move::new_promotion(from, to, promo, true));
+                    }
+                } else {
+                    moves.push(Move::new(from, to, capture_flags(to)));
                 }
-            } else {
-                moves.push(Move {
-                    from,
-                    to,
-                    promotion: None,
-                });
             }
-        }
 
-        // ---------- RIGHT DIAGONAL CAPTURES ----------
-        let right_attacks = if color == Color::White {
-            (pawns << 9) & NOT_H_FILE
-        } else {
-            (pawns >> 7) & NOT_A_FILE
-        };
-
-        let right_caps = right_attacks & capture_targets;
+            // ---------- RIGHT DIAGONAL CAPTURES ----------
+            let right_attacks = if color == Color::White {
+                shift_north_east(pawns)
+            } else {
+                shift_south_east(pawns)
+            };
 
-        // Recover FROM square
-        let right_from_shift = if color == Color::White { -9 } else { 7 };
-        // Compute TO square
-        let right_delta = if color == Color::White { 9 } else { -7 };
+            let right_caps = right_attacks & capture_targets;
 
-        let mut right_from = shift(right_caps, right_from_shift);
+            // Recover FROM square
+            let right_from_shift = if color == Color::White { -9 } else { 7 };
 
-        while let Some(from) = pop_lsb(&mut right_from) {
-            let to = (from as i16 + right_delta) as Square;
+            for from in shift(right_caps, right_from_shift) {
+                let to = forward(from, 1, 1);
 
-            if get_bit(promotion_rank, to) {
-                for &promo in &[
-                    PieceType::Queen,
-                    PieceType::Rook,
-                    PieceType::Bishop,
-                    PieceType::Knight,
-                ] {
-                    moves.push(Move {
-                        from,
-                        to,
-                        promotion: Some(promo),
-                    });
+                if get_bit(promotion_rank, to) {
+                    for &promo in &[
+                        PieceType::Queen,
+                        PieceType::Rook,
+                        PieceType::Bishop,
+                        PieceType::Knight,
+                    ] {
+                        moves.push(Move::new_promotion(from, to, promo, true));
+                    }
+                } else {
+                    moves.push(Move::new(from, to, capture_flags(to)));
                 }
-            } else {
-                moves.push(Move {
-                    from,
-                    to,
-                    promotion: None,
-                });
             }
         }
 
+        // Squares the other pieces may move to
+        let mut piece_targets = Bitboard::EMPTY;
+        if noisy {
+            piece_targets |= opp_pieces;
+        }
+        if quiet {
+            piece_targets |= empty;
+        }
+        piece_targets &= targets & !own_pieces;
+
         // === KNIGHTS ===
         let knights = self.pieces[color as usize][PieceType::Knight as usize];
-        let mut knight_bb = knights;
-        while let Some(from) = pop_lsb(&mut knight_bb) {
-            let attacks = KNIGHT_ATTACKS[from as usize] & !own_pieces;
+        for from in knights {
+            let attacks = KNIGHT_ATTACKS[from.index()] & piece_targets;
             add_moves!(from, attacks);
         }
         // === BISHOPS ===
         let bishops = self.pieces[color as usize][PieceType::Bishop as usize];
-        let mut bishop_bb = bishops;
-        while let Some(from) = pop_lsb(&mut bishop_bb) {
-            let attacks = self.bishop_attacks(from, occupied) & !own_pieces;
+        for from in bishops {
+            let attacks = bishop_attacks(from, occupied) & piece_targets;
             add_moves!(from, attacks);
         }
 
         // === ROOKS ===
         let rooks = self.pieces[color as usize][PieceType::Rook as usize];
-        let mut rook_bb = rooks;
-        while let Some(from) = pop_lsb(&mut rook_bb) {
-            let attacks = self.rook_attacks(from, occupied) & !own_pieces;
+        for from in rooks {
+            let attacks = rook_attacks(from, occupied) & piece_targets;
             add_moves!(from, attacks);
         }
 
         // === QUEENS ===
         let queens = self.pieces[color as usize][PieceType::Queen as usize];
-        let mut queen_bb = queens;
-        while let Some(from) = pop_lsb(&mut queen_bb) {
-            let attacks = self.queen_attacks(from, occupied) & !own_pieces;
+        for from in queens {
+            let attacks = queen_attacks(from, occupied) & piece_targets;
             add_moves!(from, attacks);
         }
-        // === KING ===
-        let king_sq = lsb(self.pieces[color as usize][PieceType::King as usize]);
-        let king_attacks = KING_ATTACKS[king_sq as usize] & !own_pieces;
-        add_moves!(king_sq, king_attacks);
-
-        // === CASTLING (pseudo-legal only) ===
-        if !self.is_in_check(color) {
-            let kingside = if color == Color::White {
-                0b0001
-            } else {
-                0b0100
-            };
-            let queenside = if color == Color::White {
-                0b0010
-            } else {
-                0b1000
-            };
+    }
 
-            let back_rank = if color == Color::White {
-                RANK_1
+    /// King steps (not castling) to the squares in `targets`
+    pub(crate) fn generate_king_moves(&self, moves: &mut MoveList, targets: Bitboard) {
+        let color = self.turn;
+        let opp_pieces = self.all_pieces(color.opposite());
+        let own_pieces = self.all_pieces(color);
+        let king_sq = self.king_square(color);
+        for to in KING_ATTACKS[king_sq.index()] & targets & !own_pieces {
+            let flags = if get_bit(opp_pieces, to) {
+                Move::CAPTURE
             } else {
-                RANK_8
+                Move::QUIET
             };
-
-            if self.castling_rights & kingside != 0 {
-                let path = if color == Color::White {
-                    0b01100000
-                } else {
-                    0b01100000 << 56
-                };
-                if occupied & path == 0 {
-                    moves.push(Move {
-                        from: king_sq,
-                        to: king_sq + 2,
-                        promotion: None,
-                    }); // kingside
-                }
-            }
-
-            if self.castling_rights & queenside != 0 {
-                let path = if color == Color::White {
-                    0b00011100
-                } else {
-                    0b00011100 << 56
-                };
-                if occupied & path == 0 {
-                    moves.push(Move {
-                        from: king_sq,
-                        to: king_sq - 2,
-                        promotion: None,
-                    }); // queenside
-                }
-            }
+            moves.push(Move::new(king_sq, to, flags));
         }
+    }
+
+    /// Castling moves for the side to move.
+    /// These are fully legal: the king may not be in check, the squares between
+    /// king and rook must be empty, and the king may not cross or land on an attacked square.
+    pub(crate) fn generate_castling_moves(&self, moves: &mut MoveList) {
+        let color = self.turn;
+        let opp_color = color.opposite();
+        let king_sq = self.king_square(color);
+        let rooks = self.pieces[color as usize][PieceType::Rook as usize];
 
-        moves
-    }
-
-    // Helper ray attack functions (simple but correct)
-    // fn bishop_attacks(&self, sq: Square, occupied: Bitboard) -> Bitboard {
-    //     self.diagonal_attacks(sq, occupied)
-    // }
-
-    // fn rook_attacks(&self, sq: Square, occupied: Bitboard) -> Bitboard {
-    //     self.rank_attacks(sq, occupied) | self.file_attacks(sq, occupied)
-    // }
-    fn bishop_attacks(&self, sq: Square, occupied: Bitboard) -> Bitboard {
-        self.diagonal_attacks(sq, occupied) | self.antidiagonal_attacks(sq, occupied)
-    }
-
-    fn rook_attacks(&self, sq: Square, occupied: Bitboard) -> Bitboard {
-        self.rank_attacks(sq, occupied) | self.file_attacks(sq, occupied)
-    }
-    fn queen_attacks(&self, sq: Square, occupied: Bitboard) -> Bitboard {
-        self.bishop_attacks(sq, occupied) | self.rook_attacks(sq, occupied)
-    }
-
-    // fn diagonal_attacks(&self, sq: Square, occupied: Bitboard) -> Bitboard {
-    //     let mut attacks = 0;
-
-    //     let file = (sq % 8) as i8;
-    //     let rank = (sq / 8) as i8;
-
-    //     // NE
-    //     for i in 1..8 {
-    //         if file + i > 7 || rank + i > 7 {
-    //             break;
-    //         }
-    //         let target = sq + i as Square * 9;
-    //         set_bit(&mut attacks, target);
-    //         if get_bit(occupied, target) {
-    //             break;
-    //         }
-    //     }
-    //     // NW
-    //     for i in 1..8 {
-    //         if file - i < 0 || rank + i > 7 {
-    //             break;
-    //         }
-    //         let target = sq + i as Square * 7;
-    //         set_bit(&mut attacks, target);
-    //         if get_bit(occupied, target) {
-    //             break;
-    //         }
-    //     }
-    //     // SE, SW similarly...
-    //     //
-    //     // SE
-    //     for i in 1..8 {
-    //         if file + i > 7 || rank - i < 0 {
-    //             break;
-    //         }
-    //         let target = sq - i as Square * 7;
-    //         set_bit(&mut attacks, target);
-    //         if get_bit(occupied, target) {
-    //             break;
-    //         }
-    //     }
-    //     // SW
-    //     for i in 1..8 {
-    //         if file - i < 0 || rank - i < 0 {
-    //             break;
-    //         }
-    //         let target = sq - i as Square * 9;
-    //         set_bit(&mut attacks, target);
-    //         if get_bit(occupied, target) {
-    //             break;
-    //         }
-    //     }
-    //     attacks
-    // }
-
-    // fn rank_attacks(&self, sq: Square, occupied: Bitboard) -> Bitboard {
-    //     let mut attacks = 0;
-
-    //     let rank = (sq / 8) as i8;
-
-    //     // Right | East
-    //     for i in 1..8 {
-    //         if rank + i > 7 {
-    //             break;
-    //         }
-    //         let target = sq + i as Square;
-    //         set_bit(&mut attacks, target);
-    //         if get_bit(occupied, target) {
-    //             break;
-    //         }
-    //     }
-    //     // Left | West
-    //     for i in 1..8 {
-    //         if rank - i < 0 {
-    //             break;
-    //         }
-    //         let target = sq - i as Square;
-    //         set_bit(&mut attacks, target);
-    //         if get_bit(occupied, target) {
-    //             break;
-    //         }
-    //     }
-    //     attacks
-    // }
-    // fn file_attacks(&self, sq: Square, occupied: Bitboard) -> Bitboard {
-    //     let mut attacks = 0;
-
-    //     let file = (sq % 8) as i8;
-
-    //     // Up | North
-    //     //
-    //     for i in 1..8 {
-    //         if file + i > 7 {
-    //             break;
-    //         }
-    //         let target = sq + i as Square * 8;
-    //         set_bit(&mut attacks, target);
-    //         if get_bit(occupied, target) {
-    //             break;
-    //         }
-    //     }
-    //     // Down | South
-    //     for i in 1..8 {
-    //         if file - i < 0 {
-    //             break;
-    //         }
-    //         let target = sq - i as Square * 8;
-    //         set_bit(&mut attacks, target);
-    //         if get_bit(occupied, target) {
-    //             break;
-    //         }
-    //     }
-    //     attacks
-    // }
-    fn diagonal_attacks(&self, sq: Square, occupied: Bitboard) -> Bitboard {
-        self.ray_attack(sq as i16, 9, occupied) | self.ray_attack(sq as i16, -9, occupied)
-    }
-
-    fn antidiagonal_attacks(&self, sq: Square, occupied: Bitboard) -> Bitboard {
-        self.ray_attack(sq as i16, 7, occupied) | self.ray_attack(sq as i16, -7, occupied)
-    }
-
-    fn rank_attacks(&self, sq: Square, occupied: Bitboard) -> Bitboard {
-        self.ray_attack(sq as i16, 1, occupied) | self.ray_attack(sq as i16, -1, occupied)
-    }
-
-    fn file_attacks(&self, sq: Square, occupied: Bitboard) -> Bitboard {
-        self.ray_attack(sq as i16, 8, occupied) | self.ray_attack(sq as i16, -8, occupied)
-    }
-
-    /// Generic ray in one direction until blocked or edge
-    fn ray_attack(&self, sq: i16, direction: i8, occupied: Bitboard) -> Bitboard {
-        let mut attacks: Bitboard = 0;
-        let mut cur = sq;
-        loop {
-            let prev_file = cur % 8;
-            cur += direction as i16;
-            if cur < 0 || cur >= 64 {
-                break;
+        for (side, path) in CASTLING_PATHS[color as usize].iter().enumerate() {
+            if self.castling_rights & path.right == 0
+                || king_sq != path.king_from
+                || !get_bit(rooks, path.rook_from)
+                || !(self.occupied & path.empty).is_empty()
+            {
+                continue;
             }
-            let new_file = cur % 8;
 
-            // file wrap detection
-            if (prev_file - new_file).abs() > 2 {
-                break;
+            if self.is_square_attacked(king_sq, opp_color) {
+                return;
             }
 
-            let cur_sq = cur as Square;
-            set_bit(&mut attacks, cur_sq);
+            let crosses_attack = path
+                .safe
+                .into_iter()
+                .any(|sq| self.is_square_attacked(sq, opp_color));
 
-            if get_bit(occupied, cur_sq) {
-                break; // blocked by any piece
+            if !crosses_attack {
+                let flags = if side == 0 {
+                    Move::KING_CASTLE
+                } else {
+                    Move::QUEEN_CASTLE
+                };
+                moves.push(Move::new(path.king_from, path.king_to, flags));
             }
         }
-        attacks
     }
+
     /// Returns true if the king of the given color is in check
     pub fn is_in_check(&self, color: Color) -> bool {
         let king_sq = self.king_square(color);
@@ -541,64 +539,123 @@ impl Board {
 
     /// Helper: get the square of the king for the given color
     /// Assumes there is exactly one king (panics otherwise – safe in valid positions)
-    fn king_square(&self, color: Color) -> Square {
+    pub fn king_square(&self, color: Color) -> Square {
         lsb(self.pieces[color as usize][PieceType::King as usize])
     }
 
     /// Returns true if the given square is attacked by the given color
-    fn is_square_attacked(&self, sq: Square, by_color: Color) -> bool {
+    pub fn is_square_attacked(&self, sq: Square, by_color: Color) -> bool {
         let occupied = self.occupied;
-        let own_pieces = self.all_pieces(by_color); // not needed for blockers in pawn/knight/king
-        let opp_pieces = self.all_pieces(if by_color == Color::White {
-            Color::Black
-        } else {
-            Color::White
-        });
+        // Pawn attacks: a pawn of `by_color` attacks `sq` if a pawn of the other
+        // color standing on `sq` would attack it
+        let pawn_attackers = pawn_attacks(sq.bit(), by_color.opposite());
 
-        // Pawn attacks (direction depends on attacker color)
-        let pawn_attacks = if by_color == Color::White {
-            // White pawns attack upwards
-            ((1u64 << sq) >> 7) & NOT_H_FILE | ((1u64 << sq) >> 9) & NOT_A_FILE
-        } else {
-            // Black pawns attack downwards
-            ((1u64 << sq) << 7) & NOT_A_FILE | ((1u64 << sq) << 9) & NOT_H_FILE
-        };
-
-        if pawn_attacks & self.pieces[by_color as usize][PieceType::Pawn as usize] != 0 {
+        if !(pawn_attackers & self.pieces[by_color as usize][PieceType::Pawn as usize]).is_empty() {
             return true;
         }
 
         // Knight attacks
-        if KNIGHT_ATTACKS[sq as usize] & self.pieces[by_color as usize][PieceType::Knight as usize]
-            != 0
+        if !(KNIGHT_ATTACKS[sq.index()]
+            & self.pieces[by_color as usize][PieceType::Knight as usize])
+            .is_empty()
         {
             return true;
         }
 
         // King attacks (adjacent)
-        if KING_ATTACKS[sq as usize] & self.pieces[by_color as usize][PieceType::King as usize] != 0
+        if !(KING_ATTACKS[sq.index()] & self.pieces[by_color as usize][PieceType::King as usize])
+            .is_empty()
         {
             return true;
         }
 
         // Bishop / Queen diagonal attacks
-        if self.bishop_attacks(sq, occupied)
+        if !(bishop_attacks(sq, occupied)
             & (self.pieces[by_color as usize][PieceType::Bishop as usize]
-                | self.pieces[by_color as usize][PieceType::Queen as usize])
-            != 0
+                | self.pieces[by_color as usize][PieceType::Queen as usize]))
+            .is_empty()
         {
             return true;
         }
 
         // Rook / Queen rank/file attacks
-        if self.rook_attacks(sq, occupied)
+        if !(rook_attacks(sq, occupied)
             & (self.pieces[by_color as usize][PieceType::Rook as usize]
-                | self.pieces[by_color as usize][PieceType::Queen as usize])
-            != 0
+                | self.pieces[by_color as usize][PieceType::Queen as usize]))
+            .is_empty()
         {
             return true;
         }
 
         false
     }
+
+    /// Pieces of either color attacking `sq`, with sliders seeing through the
+    /// given occupancy. Pieces not in `occupied` are left out, so removing a
+    /// piece from it both takes that attacker away and opens its line to x-rays.
+    pub fn attackers_to(&self, sq: Square, occupied: Bitboard) -> Bitboard {
+        let [white, black] = &self.pieces;
+        let diagonal = white[PieceType::Bishop as usize]
+            | white[PieceType::Queen as usize]
+            | black[PieceType::Bishop as usize]
+            | black[PieceType::Queen as usize];
+        let straight = white[PieceType::Rook as usize]
+            | white[PieceType::Queen as usize]
+            | black[PieceType::Rook as usize]
+            | black[PieceType::Queen as usize];
+
+        ((pawn_attacks(sq.bit(), Color::Black) & white[PieceType::Pawn as usize])
+            | (pawn_attacks(sq.bit(), Color::White) & black[PieceType::Pawn as usize])
+            | (KNIGHT_ATTACKS[sq.index()]
+                & (white[PieceType::Knight as usize] | black[PieceType::Knight as usize]))
+            | (KING_ATTACKS[sq.index()]
+                & (white[PieceType::King as usize] | black[PieceType::King as usize]))
+            | (bishop_attacks(sq, occupied) & diagonal)
+            | (rook_attacks(sq, occupied) & straight))
+            & occupied
+    }
+
+    /// Pieces of `by_color` attacking `sq`, with sliders seeing through the given occupancy
+    pub(crate) fn attackers_by(&self, sq: Square, by_color: Color, occupied: Bitboard) -> Bitboard {
+        let theirs = &self.pieces[by_color as usize];
+        let diagonal = theirs[PieceType::Bishop as usize] | theirs[PieceType::Queen as usize];
+        let straight = theirs[PieceType::Rook as usize] | theirs[PieceType::Queen as usize];
+
+        (pawn_attacks(sq.bit(), by_color.opposite()) & theirs[PieceType::Pawn as usize])
+            | (KNIGHT_ATTACKS[sq.index()] & theirs[PieceType::Knight as usize])
+            | (KING_ATTACKS[sq.index()] & theirs[PieceType::King as usize])
+            | (bishop_attacks(sq, occupied) & diagonal)
+            | (rook_attacks(sq, occupied) & straight)
+    }
+
+    /// Every square attacked by at least one piece of `color`
+    pub fn attacked_squares(&self, color: Color) -> Bitboard {
+        let ours = &self.pieces[color as usize];
+        let mut attacked = pawn_attacks(ours[PieceType::Pawn as usize], color);
+        for (pt, &pieces) in ours.iter().enumerate().skip(1) {
+            let piece_type = PieceType::from_usize(pt).unwrap();
+            for sq in pieces {
+                attacked |= piece_attacks(piece_type, color, sq, self.occupied);
+            }
+        }
+        attacked
+    }
+}
+
+/// Squares attacked by a piece of the given type and color on `sq`, with
+/// sliders stopped by `occupied`. Only pawns need the color.
+pub fn piece_attacks(
+    piece_type: PieceType,
+    color: Color,
+    sq: Square,
+    occupied: Bitboard,
+) -> Bitboard {
+    match piece_type {
+        PieceType::Pawn => pawn_attacks(sq.bit(), color),
+        PieceType::Knight => KNIGHT_ATTACKS[sq.index()],
+        PieceType::Bishop => bishop_attacks(sq, occupied),
+        PieceType::Rook => rook_attacks(sq, occupied),
+        PieceType::Queen => queen_attacks(sq, occupied),
+        PieceType::King => KING_ATTACKS[sq.index()],
+    }
 }
@@ -0,0 +1,202 @@
+use std::error::Error;
+use std::fmt;
+
+use crate::constants::*;
+use crate::fen::algebraic_to_square;
+use crate::initialize_board::Board;
+use crate::pseudo_legal_move_generation::{Move, square_to_algebraic};
+use crate::square::*;
+
+/// Why a SAN string could not be turned into a move
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SanError {
+    /// Not recognisable as a move
+    Malformed(String),
+    /// Well-formed, but no legal move matches
+    Illegal(String),
+    /// More than one legal move matches; the candidates are listed
+    Ambiguous { san: String, candidates: Vec<Move> },
+}
+
+impl fmt::Display for SanError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SanError::Malformed(san) => write!(f, "'{}' is not a valid SAN move", san),
+            SanError::Illegal(san) => write!(f, "'{}' is not a legal move here", san),
+            SanError::Ambiguous { san, candidates } => write!(
+                f,
+                "'{}' is ambiguous: {} legal moves match",
+                san,
+                candidates.len()
+            ),
+        }
+    }
+}
+
+impl Error for SanError {}
+
+impl Move {
+    /// Standard Algebraic Notation for this move, which must be legal on `board`,
+    /// e.g. "Nbd7", "exd6", "e8=Q+", "O-O-O#"
+    pub fn to_san(&self, board: &Board) -> String {
+        let Some(piece) = self.moving_piece(board) else {
+            return String::new();
+        };
+        let mut san = String::new();
+
+        if self.is_castling() {
+            san.push_str(if self.flags() == Move::KING_CASTLE {
+                "O-O"
+            } else {
+                "O-O-O"
+            });
+        } else {
+            let is_capture = self.is_capture();
+
+            if piece == PieceType::Pawn {
+                if is_capture {
+                    san.push(self.from().file().to_char());
+                }
+            } else {
+                san.push(piece.to_char());
+
+                // Other pieces of the same kind that could also go to 'to'
+                let rivals: Vec<Move> = board
+                    .generate_legal_moves()
+                    .into_iter()
+                    .filter(|m| {
+                        m.to() == self.to()
+                            && m.from() != self.from()
+                            && m.moving_piece(board) == Some(piece)
+                    })
+                    .collect();
+                if !rivals.is_empty() {
+                    let from = square_to_algebraic(self.from());
+                    if rivals.iter().all(|m| m.from().file() != self.from().file()) {
+                        san.push_str(&from[..1]);
+                    } else if rivals.iter().all(|m| m.from().rank() != self.from().rank()) {
+                        san.push_str(&from[1..]);
+                    } else {
+                        san.push_str(&from);
+                    }
+                }
+            }
+
+            if is_capture {
+                san.push('x');
+            }
+            san.push_str(&square_to_algebraic(self.to()));
+
+            if let Some(promo) = self.promotion() {
+                san.push('=');
+                san.push(promo.to_char());
+            }
+        }
+
+        let mut after = *board;
+        after.apply_move(self);
+        if after.is_in_check(after.turn) {
+            san.push(if after.generate_legal_moves().is_empty() {
+                '#'
+            } else {
+                '+'
+            });
+        }
+
+        san
+    }
+}
+
+impl Board {
+    /// Find the legal move written in SAN.
+    ///
+    /// Accepts the usual loose forms as well: "0-0" for "O-O", a missing "=" in
+    /// promotions ("e8Q"), lowercase promotion letters, extra disambiguation,
+    /// and trailing "+", "#", "!" or "?".
+    pub fn parse_san(&self, san: &str) -> Result<Move, SanError> {
+        let malformed = || SanError::Malformed(san.to_string());
+        let text = san.trim().trim_end_matches(['+', '#', '!', '?']);
+
+        let legal = self.generate_legal_moves();
+
+        // Castling
+        let castle_flags = match text {
+            "O-O" | "0-0" => Some(Move::KING_CASTLE),
+            "O-O-O" | "0-0-0" => Some(Move::QUEEN_CASTLE),
+            _ => None,
+        };
+        if let Some(flags) = castle_flags {
+            return legal
+                .into_iter()
+                .find(|m| m.flags() == flags)
+                .ok_or_else(|| SanError::Illegal(san.to_string()));
+        }
+
+        let mut chars: Vec<char> = text.chars().collect();
+
+        // Piece letter (uppercase only, so "b" stays a file)
+        let piece = match chars.first() {
+            Some(&c) if c.is_ascii_uppercase() => {
+                chars.remove(0);
+                PieceType::from_char(c).ok_or_else(malformed)?
+            }
+            Some(_) => PieceType::Pawn,
+            None => return Err(malformed()),
+        };
+
+        // Promotion: "=Q", "Q" or "q" after the destination rank
+        let mut promotion = None;
+        if let Some(&last) = chars.last()
+            && !last.is_ascii_digit()
+            && chars.len() >= 3
+        {
+            let promo = PieceType::from_char(last)
+                .filter(|p| !matches!(p, PieceType::Pawn | PieceType::King))
+                .ok_or_else(malformed)?;
+            promotion = Some(promo);
+            chars.pop();
+            if chars.last() == Some(&'=') {
+                chars.pop();
+            }
+        }
+
+        // Destination: the last two characters
+        if chars.len() < 2 {
+            return Err(malformed());
+        }
+        let dest: String = chars.split_off(chars.len() - 2).into_iter().collect();
+        let to = algebraic_to_square(&dest).ok_or_else(malformed)?;
+
+        // Whatever is left is disambiguation and capture marks
+        let mut from_file = None;
+        let mut from_rank = None;
+        for c in chars {
+            match c {
+                'a'..='h' => from_file = File::from_char(c),
+                '1'..='8' => from_rank = Rank::from_char(c),
+                'x' | 'X' | ':' | '-' => {}
+                _ => return Err(malformed()),
+            }
+        }
+
+        let candidates: Vec<Move> = legal
+            .into_iter()
+            .filter(|m| {
+                m.to() == to
+                    && m.moving_piece(self) == Some(piece)
+                    && from_file.is_none_or(|f| m.from().file() == f)
+                    && from_rank.is_none_or(|r| m.from().rank() == r)
+                    && m.promotion() == promotion
+            })
+            .collect();
+
+        match candidates.len() {
+            0 => Err(SanError::Illegal(san.to_string())),
+            1 => Ok(candidates[0]),
+            _ => Err(SanError::Ambiguous {
+                san: san.to_string(),
+                candidates,
+            }),
+        }
+    }
+}
@@ -0,0 +1,104 @@
+use crate::constants::*;
+use crate::initialize_board::Board;
+use crate::magic::*;
+use crate::pseudo_legal_move_generation::Move;
+use crate::square::*;
+
+/// Piece values used by static exchange evaluation, indexed by `PieceType`.
+/// The king is worth more than everything else together, so it only ever
+/// captures last.
+pub const SEE_VALUES: [i32; 6] = [100, 320, 330, 500, 900, 20000];
+
+impl Board {
+    /// Static exchange evaluation: the material `m` wins or loses once every
+    /// capture on its target square has been played out, each side always
+    /// capturing with its least valuable piece and free to stop when
+    /// continuing would lose. Sliders lined up behind a capturer join in as
+    /// the pieces in front of them leave. Pins are not taken into account.
+    pub fn see(&self, m: Move) -> i32 {
+        let (from, to) = (m.from(), m.to());
+        if m.is_castling() {
+            return 0;
+        }
+        let Some(mover) = self.piece_on(from) else {
+            return 0;
+        };
+
+        // Gains so far, from the point of view of the side making each capture
+        let mut gain = [0i32; 32];
+        let mut occupied = self.occupied & !from.bit();
+        let mut on_target = SEE_VALUES[mover.piece_type as usize];
+
+        if m.is_en_passant() {
+            let captured = Square::from_coords(to.file(), from.rank());
+            occupied &= !captured.bit();
+            gain[0] = SEE_VALUES[PieceType::Pawn as usize];
+        } else if let Some(captured) = self.piece_on(to) {
+            gain[0] = SEE_VALUES[captured.piece_type as usize];
+        }
+        if let Some(promo) = m.promotion() {
+            on_target = SEE_VALUES[promo as usize];
+            gain[0] += on_target - SEE_VALUES[PieceType::Pawn as usize];
+        }
+        occupied |= to.bit();
+
+        let diagonal =
+            self.pieces_of_type(PieceType::Bishop) | self.pieces_of_type(PieceType::Queen);
+        let straight = self.pieces_of_type(PieceType::Rook) | self.pieces_of_type(PieceType::Queen);
+        let promotion_rank = RANK_1 | RANK_8;
+
+        let mut attackers = self.attackers_to(to, occupied) & !to.bit();
+        let mut side = mover.color.opposite();
+        let mut depth = 1;
+        while depth < gain.len() {
+            let ours = attackers & self.all_pieces(side);
+            // Least valuable attacker first
+            let Some((piece_type, sq)) = (0..6).find_map(|pt| {
+                let sq = (ours & self.pieces[side as usize][pt]).lsb()?;
+                Some((PieceType::from_usize(pt).unwrap(), sq))
+            }) else {
+                break;
+            };
+            // The king cannot capture onto a square the other side still attacks
+            if piece_type == PieceType::King
+                && !(attackers & self.all_pieces(side.opposite())).is_empty()
+            {
+                break;
+            }
+
+            gain[depth] = on_target - gain[depth - 1];
+            on_target = SEE_VALUES[piece_type as usize];
+            if piece_type == PieceType::Pawn && promotion_rank.contains(to) {
+                let queen = SEE_VALUES[PieceType::Queen as usize];
+                gain[depth] += queen - on_target;
+                on_target = queen;
+            }
+
+            // Take the capturer off the board and let x-rays through
+            occupied &= !sq.bit();
+            attackers &= !sq.bit();
+            attackers |= ((bishop_attacks(to, occupied) & diagonal)
+                | (rook_attacks(to, occupied) & straight))
+                & occupied;
+            side = side.opposite();
+            depth += 1;
+        }
+
+        // Either side may stand pat instead of capturing
+        for d in (1..depth).rev() {
+            gain[d - 1] = -(-gain[d - 1]).max(gain[d]);
+        }
+        gain[0]
+    }
+
+    /// Whether `m` wins at least `threshold` by static exchange evaluation
+    pub fn see_ge(&self, m: Move, threshold: i32) -> bool {
+        self.see(m) >= threshold
+    }
+
+    /// Pieces of one type, both colors
+    fn pieces_of_type(&self, piece_type: PieceType) -> Bitboard {
+        self.pieces[Color::White as usize][piece_type as usize]
+            | self.pieces[Color::Black as usize][piece_type as usize]
+    }
+}
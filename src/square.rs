@@ -0,0 +1,260 @@
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::constants::Bitboard;
+
+/// A file, a (0) to h (7)
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct File(u8);
+
+/// A rank, 1 (0) to 8 (7)
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct Rank(u8);
+
+/// A square, a1 (0) to h8 (63), numbered rank by rank
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Square(u8);
+
+impl File {
+    pub const A: File = File(0);
+    pub const B: File = File(1);
+    pub const C: File = File(2);
+    pub const D: File = File(3);
+    pub const E: File = File(4);
+    pub const F: File = File(5);
+    pub const G: File = File(6);
+    pub const H: File = File(7);
+
+    /// None unless `index` is 0..=7
+    pub const fn new(index: u8) -> Option<File> {
+        if index < 8 { Some(File(index)) } else { None }
+    }
+
+    /// Parse 'a'..='h'
+    pub const fn from_char(c: char) -> Option<File> {
+        match c {
+            'a'..='h' => Some(File(c as u8 - b'a')),
+            _ => None,
+        }
+    }
+
+    pub const fn to_char(self) -> char {
+        (b'a' + self.0) as char
+    }
+
+    pub const fn index(self) -> usize {
+        self.0 as usize
+    }
+
+    pub const fn to_u8(self) -> u8 {
+        self.0
+    }
+}
+
+impl Rank {
+    pub const ONE: Rank = Rank(0);
+    pub const TWO: Rank = Rank(1);
+    pub const THREE: Rank = Rank(2);
+    pub const FOUR: Rank = Rank(3);
+    pub const FIVE: Rank = Rank(4);
+    pub const SIX: Rank = Rank(5);
+    pub const SEVEN: Rank = Rank(6);
+    pub const EIGHT: Rank = Rank(7);
+
+    /// None unless `index` is 0..=7
+    pub const fn new(index: u8) -> Option<Rank> {
+        if index < 8 { Some(Rank(index)) } else { None }
+    }
+
+    /// Parse '1'..='8'
+    pub const fn from_char(c: char) -> Option<Rank> {
+        match c {
+            '1'..='8' => Some(Rank(c as u8 - b'1')),
+            _ => None,
+        }
+    }
+
+    pub const fn to_char(self) -> char {
+        (b'1' + self.0) as char
+    }
+
+    pub const fn index(self) -> usize {
+        self.0 as usize
+    }
+
+    pub const fn to_u8(self) -> u8 {
+        self.0
+    }
+
+    /// The same rank seen from the other side of the board
+    pub const fn flip(self) -> Rank {
+        Rank(7 - self.0)
+    }
+}
+
+impl Square {
+    /// None unless `index` is 0..=63
+    pub const fn new(index: u8) -> Option<Square> {
+        if index < 64 {
+            Some(Square(index))
+        } else {
+            None
+        }
+    }
+
+    /// For indices that are in range by construction, such as bit positions
+    /// of a bitboard. Out-of-range input is a bug; it panics in debug builds.
+    #[inline(always)]
+    pub const fn from_index(index: u8) -> Square {
+        debug_assert!(index < 64, "square index out of range");
+        Square(index & 63)
+    }
+
+    pub const fn from_coords(file: File, rank: Rank) -> Square {
+        Square(rank.0 * 8 + file.0)
+    }
+
+    /// Index for tables with one entry per square
+    #[inline(always)]
+    pub const fn index(self) -> usize {
+        self.0 as usize
+    }
+
+    pub const fn to_u8(self) -> u8 {
+        self.0
+    }
+
+    pub const fn file(self) -> File {
+        File(self.0 % 8)
+    }
+
+    pub const fn rank(self) -> Rank {
+        Rank(self.0 / 8)
+    }
+
+    /// The square `dx` files right and `dy` ranks up, if it is still on the board
+    pub const fn offset(self, dx: i8, dy: i8) -> Option<Square> {
+        let file = self.file().0 as i8 + dx;
+        let rank = self.rank().0 as i8 + dy;
+        if file < 0 || file > 7 || rank < 0 || rank > 7 {
+            return None;
+        }
+        Some(Square((rank * 8 + file) as u8))
+    }
+
+    /// King steps between two squares: the larger of the file and rank distances
+    pub const fn distance(self, other: Square) -> u8 {
+        let files = self.file().0.abs_diff(other.file().0);
+        let ranks = self.rank().0.abs_diff(other.rank().0);
+        if files > ranks { files } else { ranks }
+    }
+
+    /// Mirror vertically: a1 <-> a8, e2 <-> e7
+    pub const fn flip(self) -> Square {
+        Square(self.0 ^ 56)
+    }
+
+    /// A bitboard with just this square set
+    #[inline(always)]
+    pub const fn bit(self) -> Bitboard {
+        Bitboard::from_square(self)
+    }
+
+    /// All 64 squares, a1 first
+    pub fn all() -> impl Iterator<Item = Square> {
+        (0..64).map(Square)
+    }
+}
+
+/// Raw square indices convert both ways; the direction into `Square` can fail
+impl TryFrom<u8> for Square {
+    type Error = ParseSquareError;
+
+    fn try_from(index: u8) -> Result<Self, Self::Error> {
+        Square::new(index).ok_or(ParseSquareError)
+    }
+}
+
+impl From<Square> for u8 {
+    fn from(sq: Square) -> u8 {
+        sq.0
+    }
+}
+
+impl From<Square> for usize {
+    fn from(sq: Square) -> usize {
+        sq.index()
+    }
+}
+
+/// A string or index that does not name a square
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseSquareError;
+
+impl fmt::Display for ParseSquareError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "not a square")
+    }
+}
+
+impl Error for ParseSquareError {}
+
+impl FromStr for Square {
+    type Err = ParseSquareError;
+
+    /// "a1".."h8", lowercase only
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        match (chars.next(), chars.next(), chars.next()) {
+            (Some(f), Some(r), None) => {
+                let file = File::from_char(f).ok_or(ParseSquareError)?;
+                let rank = Rank::from_char(r).ok_or(ParseSquareError)?;
+                Ok(Square::from_coords(file, rank))
+            }
+            _ => Err(ParseSquareError),
+        }
+    }
+}
+
+impl fmt::Display for Square {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.file().to_char(), self.rank().to_char())
+    }
+}
+
+impl fmt::Debug for Square {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+impl fmt::Display for File {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_char())
+    }
+}
+
+impl fmt::Display for Rank {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_char())
+    }
+}
+
+macro_rules! square_constants {
+    ($($name:ident = $index:expr),* $(,)?) => {
+        $(pub const $name: Square = Square($index);)*
+    };
+}
+
+#[rustfmt::skip]
+square_constants!(
+    A1 = 0,  B1 = 1,  C1 = 2,  D1 = 3,  E1 = 4,  F1 = 5,  G1 = 6,  H1 = 7,
+    A2 = 8,  B2 = 9,  C2 = 10, D2 = 11, E2 = 12, F2 = 13, G2 = 14, H2 = 15,
+    A3 = 16, B3 = 17, C3 = 18, D3 = 19, E3 = 20, F3 = 21, G3 = 22, H3 = 23,
+    A4 = 24, B4 = 25, C4 = 26, D4 = 27, E4 = 28, F4 = 29, G4 = 30, H4 = 31,
+    A5 = 32, B5 = 33, C5 = 34, D5 = 35, E5 = 36, F5 = 37, G5 = 38, H5 = 39,
+    A6 = 40, B6 = 41, C6 = 42, D6 = 43, E6 = 44, F6 = 45, G6 = 46, H6 = 47,
+    A7 = 48, B7 = 49, C7 = 50, D7 = 51, E7 = 52, F7 = 53, G7 = 54, H7 = 55,
+    A8 = 56, B8 = 57, C8 = 58, D8 = 59, E8 = 60, F8 = 61, G8 = 62, H8 = 63,
+);
@@ -0,0 +1,192 @@
+use crate::attack::*;
+use crate::constants::*;
+use crate::initialize_board::Board;
+use crate::magic::*;
+use crate::move_list::MoveList;
+use crate::pawn_directions::*;
+use crate::pseudo_legal_move_generation::Move;
+use crate::utils::*;
+
+/// Which subset of the pseudo-legal moves a generator produces.
+///
+/// `Captures` and `Quiets` split `All` in two. `Evasions` and `QuietChecks`
+/// are narrower still and meant for search: the first when in check, the
+/// second for quiescence search.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GenType {
+    /// Every pseudo-legal move, like `generate_pseudo_moves`
+    All,
+    /// Captures, en passant and every promotion, capturing or not
+    Captures,
+    /// Everything else: pushes, non-capturing piece moves and castling
+    Quiets,
+    /// Moves that may answer a check: king steps to unattacked squares, and
+    /// under single check captures of the checker and interpositions.
+    /// Only valid while the side to move is in check.
+    Evasions,
+    /// The `Quiets` that give check, directly or by discovery
+    QuietChecks,
+}
+
+/// What it takes to check the side not to move, worked out once per position
+struct CheckInfo {
+    /// Their king
+    king: Square,
+    /// Squares from which each piece type of ours would attack their king
+    check_squares: [Bitboard; 6],
+    /// Our pieces that are the only blocker between one of our sliders and their king
+    discoverers: Bitboard,
+}
+
+impl CheckInfo {
+    fn new(board: &Board) -> Self {
+        let color = board.turn;
+        let king = board.king_square(color.opposite());
+        let occupied = board.occupied;
+        let ours = &board.pieces[color as usize];
+
+        let bishop = bishop_attacks(king, occupied);
+        let rook = rook_attacks(king, occupied);
+        let check_squares = [
+            pawn_attacks(king.bit(), color.opposite()),
+            KNIGHT_ATTACKS[king.index()],
+            bishop,
+            rook,
+            bishop | rook,
+            Bitboard::EMPTY,
+        ];
+
+        let snipers = (bishop_attacks(king, Bitboard::EMPTY)
+            & (ours[PieceType::Bishop as usize] | ours[PieceType::Queen as usize]))
+            | (rook_attacks(king, Bitboard::EMPTY)
+                & (ours[PieceType::Rook as usize] | ours[PieceType::Queen as usize]));
+        let own_pieces = board.all_pieces(color);
+        let mut discoverers = Bitboard::EMPTY;
+        for sniper in snipers {
+            let blockers = between(king, sniper) & occupied;
+            if !blockers.more_than_one() && !(blockers & own_pieces).is_empty() {
+                discoverers |= blockers;
+            }
+        }
+
+        CheckInfo {
+            king,
+            check_squares,
+            discoverers,
+        }
+    }
+
+    fn gives_check(&self, board: &Board, m: Move) -> bool {
+        let (from, to) = (m.from(), m.to());
+        let Some(piece) = board.piece_on(from) else {
+            return false;
+        };
+
+        // Direct check from the destination square
+        if !m.is_promotion()
+            && !m.is_castling()
+            && get_bit(self.check_squares[piece.piece_type as usize], to)
+        {
+            return true;
+        }
+
+        // Discovered check: the blocker steps off the line to their king
+        if get_bit(self.discoverers, from) && !get_bit(line(self.king, from), to) {
+            return true;
+        }
+
+        // Moves that change the occupancy in ways the tables above do not cover
+        if let Some(promo) = m.promotion() {
+            let occupied = (board.occupied & !from.bit()) | to.bit();
+            let attacks = match promo {
+                PieceType::Knight => KNIGHT_ATTACKS[to.index()],
+                PieceType::Bishop => bishop_attacks(to, occupied),
+                PieceType::Rook => rook_attacks(to, occupied),
+                _ => queen_attacks(to, occupied),
+            };
+            return get_bit(attacks, self.king);
+        }
+        if m.is_en_passant() {
+            // Removing the captured pawn can open a line as well
+            let captured = Square::from_coords(to.file(), from.rank());
+            let occupied = (board.occupied & !from.bit() & !captured.bit()) | to.bit();
+            let ours = &board.pieces[board.turn as usize];
+            let diagonal = ours[PieceType::Bishop as usize] | ours[PieceType::Queen as usize];
+            let straight = ours[PieceType::Rook as usize] | ours[PieceType::Queen as usize];
+            return !((bishop_attacks(self.king, occupied) & diagonal)
+                | (rook_attacks(self.king, occupied) & straight))
+                .is_empty();
+        }
+        if let Some(path) = m.castling_path() {
+            let occupied = (board.occupied & !path.king_from.bit() & !path.rook_from.bit())
+                | path.king_to.bit()
+                | path.rook_to.bit();
+            return get_bit(rook_attacks(path.rook_to, occupied), self.king);
+        }
+        false
+    }
+}
+
+impl Board {
+    /// Pseudo-legal moves of one kind, see `GenType`
+    pub fn generate_moves(&self, kind: GenType) -> Vec<Move> {
+        let mut moves = MoveList::new();
+        self.generate_moves_into(kind, &mut moves);
+        moves.to_vec()
+    }
+
+    /// Like `generate_moves`, but appends to a caller-provided list
+    pub fn generate_moves_into(&self, kind: GenType, moves: &mut MoveList) {
+        let opp_pieces = self.all_pieces(self.turn.opposite());
+        match kind {
+            GenType::All => self.generate_pseudo_moves_into(moves),
+            GenType::Captures => {
+                self.generate_piece_moves(moves, true, false, Bitboard::FULL);
+                self.generate_king_moves(moves, opp_pieces);
+            }
+            GenType::Quiets => {
+                self.generate_piece_moves(moves, false, true, Bitboard::FULL);
+                self.generate_king_moves(moves, !self.occupied);
+                self.generate_castling_moves(moves);
+            }
+            GenType::Evasions => self.generate_evasions(moves),
+            GenType::QuietChecks => {
+                let info = CheckInfo::new(self);
+                let mut quiets = MoveList::new();
+                self.generate_moves_into(GenType::Quiets, &mut quiets);
+                moves.extend(quiets.into_iter().filter(|&m| info.gives_check(self, m)));
+            }
+        }
+    }
+
+    /// Whether `m`, a legal move in this position, checks the opponent
+    pub fn gives_check(&self, m: Move) -> bool {
+        CheckInfo::new(self).gives_check(self, m)
+    }
+
+    fn generate_evasions(&self, moves: &mut MoveList) {
+        let color = self.turn;
+        let opp_color = color.opposite();
+        let king_sq = self.king_square(color);
+        let checkers = self.attackers_by(king_sq, opp_color, self.occupied);
+        debug_assert!(
+            !checkers.is_empty(),
+            "evasions asked for while not in check"
+        );
+
+        // Sliders must see through the king, or it could step back along a checking ray
+        let without_king = self.occupied & !king_sq.bit();
+        let safe = KING_ATTACKS[king_sq.index()]
+            .into_iter()
+            .filter(|&sq| self.attackers_by(sq, opp_color, without_king).is_empty())
+            .collect();
+        self.generate_king_moves(moves, safe);
+
+        // Under double check only the king can move
+        if checkers.more_than_one() {
+            return;
+        }
+        let block_or_capture = checkers | between(king_sq, lsb(checkers));
+        self.generate_piece_moves(moves, true, true, block_or_capture);
+    }
+}
@@ -0,0 +1,102 @@
+use std::error::Error;
+use std::fmt;
+
+use crate::constants::*;
+use crate::fen::algebraic_to_square;
+use crate::initialize_board::Board;
+use crate::pseudo_legal_move_generation::{Move, square_to_algebraic};
+
+/// How castling is written in coordinate notation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CastlingNotation {
+    /// The king moves two squares: "e1g1" (standard UCI)
+    #[default]
+    KingTwoSquares,
+    /// The king "captures" its own rook: "e1h1" (UCI_Chess960)
+    KingTakesRook,
+}
+
+/// Why a UCI move string could not be turned into a move
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UciMoveError {
+    /// Not of the form <from><to>[promotion], e.g. "e2e4" or "e7e8q"
+    Malformed(String),
+    /// Well-formed, but not a legal move in this position
+    Illegal(String),
+}
+
+impl fmt::Display for UciMoveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UciMoveError::Malformed(s) => write!(f, "'{}' is not a UCI move", s),
+            UciMoveError::Illegal(s) => write!(f, "'{}' is not a legal move here", s),
+        }
+    }
+}
+
+impl Error for UciMoveError {}
+
+/// Square of the rook the king castles with
+fn castling_rook(m: &Move) -> Square {
+    m.castling_path().expect("not a castling move").rook_from
+}
+
+impl Move {
+    /// Pure coordinate notation: "e2e4", "e7e8q", "e1g1"
+    pub fn to_uci(&self) -> String {
+        let mut uci = square_to_algebraic(self.from()) + &square_to_algebraic(self.to());
+        if let Some(promo) = self.promotion() {
+            uci.push(promo.to_char().to_ascii_lowercase());
+        }
+        uci
+    }
+
+    /// Coordinate notation with a choice of castling form
+    pub fn to_uci_with(&self, castling: CastlingNotation) -> String {
+        if castling == CastlingNotation::KingTakesRook && self.is_castling() {
+            return square_to_algebraic(self.from()) + &square_to_algebraic(castling_rook(self));
+        }
+        self.to_uci()
+    }
+}
+
+impl Board {
+    /// Parse a move in UCI coordinate notation and match it against the legal moves
+    pub fn parse_uci_move(&self, uci: &str) -> Result<Move, UciMoveError> {
+        self.parse_uci_move_with(uci, CastlingNotation::KingTwoSquares)
+    }
+
+    /// Like `parse_uci_move`, with a choice of castling form
+    pub fn parse_uci_move_with(
+        &self,
+        uci: &str,
+        castling: CastlingNotation,
+    ) -> Result<Move, UciMoveError> {
+        let malformed = || UciMoveError::Malformed(uci.to_string());
+
+        if !uci.is_ascii() || !(4..=5).contains(&uci.len()) {
+            return Err(malformed());
+        }
+        let from = algebraic_to_square(&uci[0..2]).ok_or_else(malformed)?;
+        let to = algebraic_to_square(&uci[2..4]).ok_or_else(malformed)?;
+        let promotion = match uci[4..].chars().next() {
+            None => None,
+            Some(c @ ('q' | 'r' | 'b' | 'n')) => PieceType::from_char(c),
+            Some(_) => return Err(malformed()),
+        };
+
+        self.generate_legal_moves()
+            .into_iter()
+            .find(|m| {
+                if m.from() != from || m.promotion() != promotion {
+                    return false;
+                }
+                if m.is_castling() && castling == CastlingNotation::KingTakesRook {
+                    castling_rook(m) == to
+                } else {
+                    m.to() == to
+                }
+            })
+            .ok_or_else(|| UciMoveError::Illegal(uci.to_string()))
+    }
+}
@@ -0,0 +1,109 @@
+use std::sync::LazyLock;
+
+use rand::RngCore;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+use crate::constants::*;
+use crate::initialize_board::Board;
+
+/// Fixed seed so every build (and every run) produces the same keys
+const ZOBRIST_SEED: u64 = 0x5EED_C0DE_CAFE_F00D;
+
+/// Random keys XOR-ed together to form a position's Zobrist hash
+pub struct ZobristKeys {
+    /// One key per [color][piece_type][square]
+    pub pieces: [[[u64; 64]; 6]; 2],
+    /// One key per castling_rights value (0b0000..=0b1111)
+    pub castling: [u64; 16],
+    /// One key per en passant file (a..h)
+    pub en_passant: [u64; 8],
+    /// XOR-ed in when Black is to move
+    pub side: u64,
+}
+
+impl ZobristKeys {
+    fn generate() -> Self {
+        let mut rng = ChaCha8Rng::seed_from_u64(ZOBRIST_SEED);
+        let mut keys = ZobristKeys {
+            pieces: [[[0; 64]; 6]; 2],
+            castling: [0; 16],
+            en_passant: [0; 8],
+            side: 0,
+        };
+
+        for color in keys.pieces.iter_mut() {
+            for piece in color.iter_mut() {
+                for key in piece.iter_mut() {
+                    *key = rng.next_u64();
+                }
+            }
+        }
+        for key in keys.castling.iter_mut() {
+            *key = rng.next_u64();
+        }
+        for key in keys.en_passant.iter_mut() {
+            *key = rng.next_u64();
+        }
+        keys.side = rng.next_u64();
+
+        keys
+    }
+
+    #[inline(always)]
+    pub fn piece(&self, color: Color, piece_type: PieceType, sq: Square) -> u64 {
+        self.pieces[color as usize][piece_type as usize][sq.index()]
+    }
+
+    /// Key for the en passant file of `sq`
+    #[inline(always)]
+    pub fn en_passant(&self, sq: Square) -> u64 {
+        self.en_passant[sq.file().index()]
+    }
+}
+
+/// Keys are generated once, on first use
+pub static ZOBRIST: LazyLock<ZobristKeys> = LazyLock::new(ZobristKeys::generate);
+
+impl Board {
+    /// Compute the Zobrist hash from scratch.
+    /// `apply_move` keeps `hash` up to date incrementally; this is the reference it is checked against.
+    pub fn compute_hash(&self) -> u64 {
+        let keys = &*ZOBRIST;
+        let mut hash = 0;
+
+        for color in [Color::White, Color::Black] {
+            for pt in 0..6 {
+                let piece_type = PieceType::from_usize(pt).unwrap();
+                for sq in self.pieces[color as usize][pt] {
+                    hash ^= keys.piece(color, piece_type, sq);
+                }
+            }
+        }
+
+        hash ^= keys.castling[self.castling_rights as usize];
+
+        if let Some(ep) = self.en_passant {
+            hash ^= keys.en_passant(ep);
+        }
+
+        if self.turn == Color::Black {
+            hash ^= keys.side;
+        }
+
+        hash
+    }
+
+    /// Compute the pawn-only Zobrist key from scratch: the piece keys of both
+    /// sides' pawns and nothing else. `put_piece`/`remove_piece` keep `pawn_key` up to date.
+    pub fn compute_pawn_key(&self) -> u64 {
+        let keys = &*ZOBRIST;
+        let mut key = 0;
+        for color in [Color::White, Color::Black] {
+            for sq in self.pieces[color as usize][PieceType::Pawn as usize] {
+                key ^= keys.piece(color, PieceType::Pawn, sq);
+            }
+        }
+        key
+    }
+}
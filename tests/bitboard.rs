@@ -0,0 +1,90 @@
+use my_own_chess_engine::*;
+
+#[test]
+fn operators_iteration_and_counts() {
+    let bb = A1.bit() | E4.bit() | H8.bit();
+    assert_eq!(bb.popcount(), 3);
+    assert!(bb.more_than_one());
+    assert!(!E4.bit().more_than_one());
+    assert!(!Bitboard::EMPTY.more_than_one());
+    assert!(bb.contains(E4) && !bb.contains(E5));
+    assert_eq!(bb & E4.bit(), E4.bit());
+    assert_eq!(bb ^ E4.bit(), A1.bit() | H8.bit());
+    assert_eq!(!Bitboard::FULL, Bitboard::EMPTY);
+
+    assert_eq!(bb.into_iter().collect::<Vec<_>>(), vec![A1, E4, H8]);
+    assert_eq!(bb.into_iter().len(), 3);
+    assert_eq!([A1, E4, H8].into_iter().collect::<Bitboard>(), bb);
+    assert_eq!(bb.lsb(), Some(A1));
+    assert_eq!(Bitboard::EMPTY.lsb(), None);
+}
+
+#[test]
+fn shifts_do_not_wrap_around_edges() {
+    assert_eq!(H4.bit().east(), Bitboard::EMPTY);
+    assert_eq!(A4.bit().west(), Bitboard::EMPTY);
+    assert_eq!(H4.bit().north_east(), Bitboard::EMPTY);
+    assert_eq!(A4.bit().north_west(), Bitboard::EMPTY);
+    assert_eq!(H4.bit().south_east(), Bitboard::EMPTY);
+    assert_eq!(A4.bit().south_west(), Bitboard::EMPTY);
+    assert_eq!(E8.bit().north(), Bitboard::EMPTY);
+    assert_eq!(E1.bit().south(), Bitboard::EMPTY);
+
+    assert_eq!(E4.bit().north(), E5.bit());
+    assert_eq!(E4.bit().south(), E3.bit());
+    assert_eq!(E4.bit().east(), F4.bit());
+    assert_eq!(E4.bit().west(), D4.bit());
+    assert_eq!(E4.bit().north_east(), F5.bit());
+    assert_eq!(E4.bit().north_west(), D5.bit());
+    assert_eq!(E4.bit().south_east(), F3.bit());
+    assert_eq!(E4.bit().south_west(), D3.bit());
+}
+
+#[test]
+fn file_rank_and_diagonal_constructors() {
+    assert_eq!(Bitboard::file(File::A), FILE_A);
+    assert_eq!(Bitboard::file(File::H), FILE_H);
+    assert_eq!(Bitboard::rank(Rank::ONE), RANK_1);
+    assert_eq!(Bitboard::rank(Rank::EIGHT), RANK_8);
+
+    assert_eq!(
+        Bitboard::diagonal(C1),
+        [C1, D2, E3, F4, G5, H6].into_iter().collect()
+    );
+    assert_eq!(
+        Bitboard::diagonal(A3),
+        [A3, B4, C5, D6, E7, F8].into_iter().collect()
+    );
+    assert_eq!(Bitboard::diagonal(D4).popcount(), 8);
+    assert_eq!(
+        Bitboard::anti_diagonal(E4),
+        [B7, C6, D5, E4, F3, G2, H1, A8].into_iter().collect()
+    );
+    assert_eq!(Bitboard::anti_diagonal(H8), H8.bit());
+
+    // The diagonals agree with what a bishop sees on an empty board
+    for sq in Square::all() {
+        assert_eq!(
+            (Bitboard::diagonal(sq) | Bitboard::anti_diagonal(sq)) ^ sq.bit(),
+            bishop_attacks(sq, Bitboard::EMPTY),
+            "diagonals through {sq}"
+        );
+    }
+}
+
+#[test]
+fn display_draws_a_grid() {
+    let grid = CASTLING_PATHS[Color::White as usize][0].empty.to_string();
+    let expected = "\
+8  . . . . . . . .
+7  . . . . . . . .
+6  . . . . . . . .
+5  . . . . . . . .
+4  . . . . . . . .
+3  . . . . . . . .
+2  . . . . . . . .
+1  . . . . . X X .
+   a b c d e f g h";
+    assert_eq!(grid, expected);
+    assert_eq!(format!("{:?}", A1.bit()), "Bitboard(0x0000000000000001)");
+}
@@ -0,0 +1,117 @@
+use my_own_chess_engine::*;
+
+const WHITE_O_O: Move = Move::new(E1, G1, Move::KING_CASTLE);
+const WHITE_O_O_O: Move = Move::new(E1, C1, Move::QUEEN_CASTLE);
+const BLACK_O_O: Move = Move::new(E8, G8, Move::KING_CASTLE);
+const BLACK_O_O_O: Move = Move::new(E8, C8, Move::QUEEN_CASTLE);
+
+fn castles(fen: &str) -> Vec<Move> {
+    // Lenient, so castling rights can disagree with where king and rooks stand
+    let board = Board::from_fen_lenient(fen).unwrap();
+    board
+        .generate_legal_moves()
+        .into_iter()
+        .filter(|m| [WHITE_O_O, WHITE_O_O_O, BLACK_O_O, BLACK_O_O_O].contains(m))
+        .collect()
+}
+
+/// Every legal king move, castling included, in sorted UCI notation
+fn king_moves(fen: &str) -> Vec<String> {
+    let board = Board::from_fen_lenient(fen).unwrap();
+    let mut moves: Vec<String> = board
+        .generate_legal_moves()
+        .into_iter()
+        .filter(|m| m.moving_piece(&board) == Some(PieceType::King))
+        .map(|m| m.to_uci())
+        .collect();
+    moves.sort();
+    moves
+}
+
+#[test]
+fn castles_both_ways_when_path_is_clear() {
+    assert_eq!(
+        castles("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1"),
+        vec![WHITE_O_O, WHITE_O_O_O]
+    );
+    assert_eq!(
+        castles("r3k2r/8/8/8/8/8/8/R3K2R b KQkq - 0 1"),
+        vec![BLACK_O_O, BLACK_O_O_O]
+    );
+}
+
+#[test]
+fn cannot_castle_out_of_check() {
+    assert!(castles("r3k2r/8/8/8/8/8/4r3/R3K2R w KQkq - 0 1").is_empty());
+}
+
+#[test]
+fn cannot_castle_through_attacked_square() {
+    // Rook on f2 covers f1: the king would cross it even though g1 is safe
+    assert_eq!(
+        king_moves("4k3/8/8/8/8/8/5r2/R3K2R w KQ - 0 1"),
+        ["e1c1", "e1d1", "e1f2"]
+    );
+    // Rook on d8 covers d1
+    assert_eq!(
+        castles("3rk3/8/8/8/8/8/8/R3K2R w KQ - 0 1"),
+        vec![WHITE_O_O]
+    );
+    // Black: bishop on a3 covers f8
+    assert_eq!(
+        king_moves("r3k2r/8/8/8/8/B7/8/4K3 b kq - 0 1"),
+        ["e8c8", "e8d7", "e8d8", "e8f7"]
+    );
+}
+
+#[test]
+fn cannot_castle_into_check() {
+    assert_eq!(
+        castles("4k3/8/8/8/8/8/6r1/R3K2R w KQ - 0 1"),
+        vec![WHITE_O_O_O]
+    );
+    assert_eq!(
+        castles("4k3/8/8/8/8/8/2r5/R3K2R w KQ - 0 1"),
+        vec![WHITE_O_O]
+    );
+}
+
+#[test]
+fn attacked_b_file_square_does_not_stop_queenside_castling() {
+    // Bishop on a2 covers b1, which the king never crosses
+    assert_eq!(
+        king_moves("4k3/8/8/8/8/8/b7/R3K2R w KQ - 0 1"),
+        ["e1c1", "e1d1", "e1d2", "e1e2", "e1f1", "e1f2", "e1g1"]
+    );
+}
+
+#[test]
+fn occupied_b_file_square_stops_queenside_castling() {
+    assert_eq!(
+        king_moves("4k3/8/8/8/8/8/8/RN2K2R w KQ - 0 1"),
+        ["e1d1", "e1d2", "e1e2", "e1f1", "e1f2", "e1g1"]
+    );
+    assert_eq!(
+        king_moves("rn2k2r/8/8/8/8/8/8/4K3 b kq - 0 1"),
+        ["e8d7", "e8d8", "e8e7", "e8f7", "e8f8", "e8g8"]
+    );
+}
+
+#[test]
+fn needs_own_rook_on_corner() {
+    // Rights claim both sides but there is no rook on a1
+    assert_eq!(
+        king_moves("4k3/8/8/8/8/8/8/4K2R w KQ - 0 1"),
+        ["e1d1", "e1d2", "e1e2", "e1f1", "e1f2", "e1g1"]
+    );
+    // An enemy knight on h1 is not a rook to castle with, and it covers f2
+    assert_eq!(
+        king_moves("4k3/8/8/8/8/8/8/R3K2n w KQ - 0 1"),
+        ["e1c1", "e1d1", "e1d2", "e1e2", "e1f1"]
+    );
+}
+
+#[test]
+fn needs_king_on_home_square() {
+    assert!(castles("4k3/8/8/8/8/8/8/R2K3R w KQ - 0 1").is_empty());
+}
@@ -0,0 +1,83 @@
+use my_own_chess_engine::*;
+
+const WAC_001: &str = r#"2rr3k/pp3pp1/1nnqbN1p/3pN3/2pP4/2P3Q1/PPB4P/R4RK1 w - - bm Qg6; id "WAC.001"; c0 "mate; in 3";"#;
+
+/// Plays a mating move when there is one, and gives up otherwise
+fn mate_in_one(board: &Board, _: SearchLimit) -> Option<Move> {
+    board.generate_legal_moves().into_iter().find(|m| {
+        let mut after = *board;
+        after.apply_move(m);
+        after.is_checkmate()
+    })
+}
+
+#[test]
+fn reads_position_and_operations() {
+    let epd = Epd::parse(WAC_001).unwrap();
+    assert_eq!(
+        epd.board,
+        Board::from_fen("2rr3k/pp3pp1/1nnqbN1p/3pN3/2pP4/2P3Q1/PPB4P/R4RK1 w - - 0 1").unwrap()
+    );
+    assert_eq!(epd.id(), Some("WAC.001"));
+    assert_eq!(epd.comment(0), Some("mate; in 3"));
+    assert_eq!(epd.comment(1), None);
+    assert_eq!(epd.operations.len(), 3);
+    assert_eq!(epd.best_moves, [epd.board.parse_san("Qg6").unwrap()]);
+    assert!(epd.avoid_moves.is_empty());
+
+    let epd = Epd::parse("4k3/8/8/8/8/8/8/R3K3 w Q - am Kd1 Kf1 ; dm 2; hmvc 12; fmvn 40; pv Ra8+")
+        .unwrap();
+    assert_eq!(epd.avoid_moves.len(), 2);
+    assert_eq!(epd.direct_mate, Some(2));
+    assert_eq!(epd.board.to_fen(), "4k3/8/8/8/8/8/8/R3K3 w Q - 12 40");
+    assert_eq!(epd.operation("pv"), Some(&["Ra8+".to_string()][..]));
+    assert_eq!(epd.operation("bm"), None);
+}
+
+#[test]
+fn reports_errors_with_position() {
+    let err = Epd::parse("8/8/8 w - -").unwrap_err();
+    assert!(matches!(err.kind, EpdErrorKind::InvalidPosition(_)));
+
+    let err = Epd::parse("4k3/8/8/8/8/8/8/4K3 w - - bm Ke3;").unwrap_err();
+    assert_eq!(err.position, 26);
+    assert!(matches!(
+        err.kind,
+        EpdErrorKind::InvalidMove(SanError::Illegal(_))
+    ));
+
+    let err = Epd::parse("4k3/8/8/8/8/8/8/4K3 w - - id \"open").unwrap_err();
+    assert_eq!((err.position, err.kind), (29, EpdErrorKind::Unterminated));
+
+    let err = Epd::parse("4k3/8/8/8/8/8/8/4K3 w - - 1x 2;").unwrap_err();
+    assert_eq!(err.kind, EpdErrorKind::InvalidOpcode("1x".to_string()));
+
+    let err =
+        Epd::parse_all("4k3/8/8/8/8/8/8/4K3 w - -\n\n4k3/8/8/8/8/8/8/4K3 w - - dm x;").unwrap_err();
+    assert_eq!(err.line, 3);
+    assert_eq!(err.kind, EpdErrorKind::InvalidOperand("dm".to_string()));
+}
+
+#[test]
+fn runs_a_suite_and_scores_it() {
+    let suite = Epd::parse_all(&format!(
+        "{}\n\
+         6k1/5ppp/8/8/8/8/8/R5K1 w - - bm Ra8#; id \"back rank\";\n\
+         6k1/5ppp/8/8/8/8/8/R5K1 w - - am Ra8#; id \"avoid mate\";\n\
+         6k1/5ppp/8/8/8/8/8/R5K1 w - - c0 \"not scored\";\n",
+        WAC_001
+    ))
+    .unwrap();
+    assert_eq!(suite.len(), 4);
+
+    let report = run_suite("tiny", &suite, SearchLimit::Depth(1), mate_in_one);
+    assert_eq!(report.solved(), 1);
+    assert_eq!(report.scored(), 3);
+    assert_eq!(report.results[1].solved, Some(true));
+    assert_eq!(report.results[3].solved, None);
+    assert_eq!(report.results[3].id, "6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1");
+
+    let text = report.to_string();
+    assert!(text.starts_with("tiny: 1/3 solved in "));
+    assert!(text.ends_with("\n  failed WAC.001: played none\n  failed avoid mate: played a1a8"));
+}
@@ -0,0 +1,106 @@
+use my_own_chess_engine::*;
+
+/// The same position with the colors swapped and the board flipped top to bottom
+fn mirror(fen: &str) -> String {
+    let fields: Vec<&str> = fen.split(' ').collect();
+    let swap_case = |s: &str| -> String {
+        s.chars()
+            .map(|c| {
+                if c.is_ascii_uppercase() {
+                    c.to_ascii_lowercase()
+                } else {
+                    c.to_ascii_uppercase()
+                }
+            })
+            .collect()
+    };
+    let placement: Vec<&str> = fields[0].split('/').rev().collect();
+    let side = if fields[1] == "w" { "b" } else { "w" };
+    let ep = match fields[3] {
+        "-" => "-".to_string(),
+        sq => format!("{}{}", &sq[..1], if &sq[1..] == "3" { 6 } else { 3 }),
+    };
+    format!(
+        "{} {} {} {} {} {}",
+        swap_case(&placement.join("/")),
+        side,
+        swap_case(fields[2]),
+        ep,
+        fields[4],
+        fields[5]
+    )
+}
+
+const FENS: [&str; 4] = [
+    "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+    "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+    "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+    "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+];
+
+#[test]
+fn symmetric_positions_evaluate_alike() {
+    let board = Board::new();
+    assert_eq!(board.evaluate(), 0);
+    assert_eq!(board.phase, MAX_PHASE);
+
+    for fen in FENS {
+        let board = Board::from_fen(fen).unwrap();
+        let mirrored = Board::from_fen(&mirror(fen)).unwrap();
+        assert_eq!(board.psq, -mirrored.psq, "{fen}");
+        assert_eq!(board.evaluate(), mirrored.evaluate(), "{fen}");
+    }
+}
+
+#[test]
+fn sums_follow_make_and_unmake() {
+    fn walk(board: &mut Board, depth: u32) {
+        assert_eq!((board.psq, board.phase), board.compute_psq());
+        if depth == 0 {
+            return;
+        }
+        for m in board.generate_legal_moves() {
+            let undo = board.make_move(&m);
+            walk(board, depth - 1);
+            board.unmake_move(&m, &undo);
+        }
+    }
+    for fen in FENS {
+        let mut board = Board::from_fen(fen).unwrap();
+        let before = board;
+        walk(&mut board, 3);
+        assert_eq!(board, before);
+    }
+}
+
+#[test]
+fn material_and_phase_drive_the_score() {
+    // White is a queen up: good for White, bad for Black to move
+    let white = Board::from_fen("4k3/8/8/8/8/8/8/3QK3 w - - 0 1").unwrap();
+    let black = Board::from_fen("4k3/8/8/8/8/8/8/3QK3 b - - 0 1").unwrap();
+    assert!(white.evaluate() > 800);
+    assert_eq!(black.evaluate(), -white.evaluate());
+    assert_eq!(white.phase, PHASE_WEIGHTS[PieceType::Queen as usize]);
+
+    // Only kings and pawns left: the endgame values alone count
+    let board = Board::from_fen("4k3/p7/8/8/8/8/P7/4K3 w - - 0 1").unwrap();
+    assert_eq!(board.phase, 0);
+    assert_eq!(board.evaluate(), board.psq.eg);
+
+    let score = Score::new(100, 20);
+    assert_eq!(score.taper(MAX_PHASE), 100);
+    assert_eq!(score.taper(0), 20);
+    assert_eq!(score.taper(MAX_PHASE / 2), 60);
+    assert_eq!(score.taper(40), 100);
+}
+
+#[test]
+fn pawns_prefer_to_advance_in_the_endgame() {
+    let back = Board::from_fen("4k3/8/8/8/8/8/P7/4K3 w - - 0 1").unwrap();
+    let forward = Board::from_fen("4k3/P7/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+    assert!(forward.evaluate() > back.evaluate());
+    assert_eq!(
+        psq_value(Color::White, PieceType::Pawn, A7),
+        -psq_value(Color::Black, PieceType::Pawn, A2)
+    );
+}
@@ -0,0 +1,213 @@
+use my_own_chess_engine::*;
+
+#[test]
+fn fen_round_trips() {
+    for fen in [
+        "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+        "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+        "rnbqkbnr/ppp1pppp/8/3pP3/8/8/PPPP1PPP/RNBQKBNR w Kq d6 0 3",
+        "4k3/8/8/8/8/8/8/4K3 b - - 42 97",
+    ] {
+        assert_eq!(Board::from_fen(fen).unwrap().to_fen(), fen);
+    }
+    assert_eq!(
+        Board::new().to_fen(),
+        "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1"
+    );
+}
+
+#[test]
+fn fen_after_moves() {
+    let mut board = Board::new();
+    board.apply_move(&Move::new(E2, E4, Move::DOUBLE_PUSH));
+    assert_eq!(
+        board.to_fen(),
+        "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1"
+    );
+}
+
+#[test]
+fn en_passant_written_only_when_capturable() {
+    // After 1. e4 no black pawn can take on e3
+    let board =
+        Board::from_fen("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1").unwrap();
+    assert_eq!(
+        board.fen().legal_en_passant_only(true).to_string(),
+        "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1"
+    );
+
+    // A capture exists
+    let fen = "rnbqkbnr/ppp1pppp/8/3pP3/8/8/PPPP1PPP/RNBQKBNR w KQkq d6 0 3";
+    let board = Board::from_fen(fen).unwrap();
+    assert_eq!(board.fen().legal_en_passant_only(true).to_string(), fen);
+
+    // A capture exists but would expose the king along the rank
+    let board = Board::from_fen("8/8/8/K2pP2r/8/8/8/7k w - d6 0 1").unwrap();
+    assert_eq!(
+        board.fen().legal_en_passant_only(true).to_string(),
+        "8/8/8/K2pP2r/8/8/8/7k w - - 0 1"
+    );
+}
+
+fn error(fen: &str) -> (FenField, usize, FenErrorKind) {
+    let err = Board::from_fen(fen).unwrap_err();
+    (err.field, err.position, err.kind)
+}
+
+#[test]
+fn strict_parsing_reports_field_and_position() {
+    use FenErrorKind::*;
+    use FenField::*;
+
+    assert_eq!(error(""), (Placement, 0, MissingField));
+    assert_eq!(
+        error("8/8/8/8/8/8/8/8 w KQkq"),
+        (EnPassant, 22, MissingField)
+    );
+    assert_eq!(
+        error("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP w KQkq - 0 1"),
+        (Placement, 0, WrongRankCount(7))
+    );
+    assert_eq!(
+        error("rnbqkbnr/ppppxppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1"),
+        (Placement, 13, InvalidCharacter('x'))
+    );
+    assert_eq!(
+        error("rnbqkbnr/pppppppp/9/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1"),
+        (Placement, 18, InvalidCharacter('9'))
+    );
+    assert_eq!(
+        error("rnbqkbnr/ppppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1"),
+        (Placement, 17, WrongFileCount)
+    );
+    assert_eq!(
+        error("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR x KQkq - 0 1"),
+        (SideToMove, 44, InvalidCharacter('x'))
+    );
+    assert_eq!(
+        error("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQxq - 0 1"),
+        (Castling, 48, InvalidCharacter('x'))
+    );
+    assert_eq!(
+        error("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KKq - 0 1"),
+        (Castling, 47, DuplicateCastling('K'))
+    );
+    assert_eq!(
+        error("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq A3 0 1"),
+        (EnPassant, 51, InvalidSquare)
+    );
+    assert_eq!(
+        error("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - x 1"),
+        (HalfMoves, 53, InvalidCounter)
+    );
+    assert_eq!(
+        error("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 0"),
+        (FullMoves, 55, InvalidCounter)
+    );
+    assert_eq!(
+        error("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1 x"),
+        (FullMoves, 57, TooManyFields)
+    );
+}
+
+#[test]
+fn strict_parsing_rejects_impossible_positions() {
+    use FenErrorKind::*;
+
+    assert_eq!(
+        error("8/8/8/8/8/8/8/4K3 w - - 0 1").2,
+        KingCount(Color::Black, 0)
+    );
+    assert_eq!(
+        error("4k3/8/8/8/8/8/8/3KK3 w - - 0 1").2,
+        KingCount(Color::White, 2)
+    );
+    assert_eq!(
+        error("4k2P/8/8/8/8/8/8/4K3 w - - 0 1").2,
+        PawnOnBackRank(H8)
+    );
+    assert_eq!(
+        error("4k3/8/8/8/8/8/8/p3K3 w - - 0 1").2,
+        PawnOnBackRank(A1)
+    );
+    // White to move while Black is in check
+    assert_eq!(error("4k2R/8/8/8/8/8/8/4K3 w - - 0 1").2, OpponentInCheck);
+    assert_eq!(error("4k3/4R3/8/8/8/8/8/4K3 w - - 0 1").2, OpponentInCheck);
+    assert_eq!(
+        error("4k3/8/8/8/8/8/8/4K2R w KQ - 0 1"),
+        (FenField::Castling, 24, CastlingWithoutKingOrRook('Q'))
+    );
+    assert_eq!(
+        error("r3k2r/8/8/8/8/8/8/R2K3R w KQkq - 0 1").2,
+        CastlingWithoutKingOrRook('K')
+    );
+    // No black pawn on d5
+    assert_eq!(
+        error("4k3/8/8/4P3/8/8/8/4K3 w - d6 0 1").2,
+        ImpossibleEnPassant(D6)
+    );
+    // Wrong rank for the side to move
+    assert_eq!(
+        error("4k3/8/8/3pP3/8/8/8/4K3 b - d6 0 1"),
+        (FenField::EnPassant, 27, InvalidSquare)
+    );
+    // The pawn could not have come from d7
+    assert_eq!(
+        error("4k3/3p4/8/3pP3/8/8/8/4K3 w - d6 0 1").2,
+        ImpossibleEnPassant(D6)
+    );
+}
+
+#[test]
+fn counters_are_optional() {
+    let board = Board::from_fen("4k3/8/8/8/8/8/8/4K3 w - -").unwrap();
+    assert_eq!(board.to_fen(), "4k3/8/8/8/8/8/8/4K3 w - - 0 1");
+}
+
+#[test]
+fn lenient_parsing_keeps_old_behaviour() {
+    let board = Board::from_fen_lenient("4k3/8/8/8/8/8/8/4K2R w KQx? - x y").unwrap();
+    assert_eq!(board.to_fen(), "4k3/8/8/8/8/8/8/4K2R w KQ - 0 1");
+
+    let board = Board::from_fen_lenient("4k3/8/8/8/8/8/8/4K3").unwrap();
+    assert_eq!(board.to_fen(), "4k3/8/8/8/8/8/8/4K3 w - - 0 1");
+
+    assert!(Board::from_fen_lenient("4k3/8/8/8/8/8/8/4K3 w - A3").is_err());
+}
+
+#[test]
+fn lenient_parsing_still_rejects_what_move_generation_cannot_handle() {
+    use FenErrorKind::*;
+    let kind = |fen: &str| Board::from_fen_lenient(fen).unwrap_err().kind;
+    // Exactly one king per side
+    assert_eq!(
+        kind("8/8/8/8/8/8/8/4K3 b - - 0 1"),
+        KingCount(Color::Black, 0)
+    );
+    assert_eq!(
+        kind("4k3/8/8/8/8/8/8/8 w - - 0 1"),
+        KingCount(Color::White, 0)
+    );
+    assert_eq!(kind("4k3/8/8/8/8/8/8/8"), KingCount(Color::White, 0));
+    assert_eq!(
+        kind("4k3/8/8/8/8/8/8/3KK3 w - - 0 1"),
+        KingCount(Color::White, 2)
+    );
+    // En passant square on the wrong rank for the side to move
+    assert_eq!(kind("4k3/8/8/8/8/8/1P6/4K3 w - a3 0 1"), InvalidSquare);
+    assert_eq!(kind("4k3/1p6/8/8/8/8/8/4K3 b - a6 0 1"), InvalidSquare);
+
+    // On the right rank but with no pawn that could have passed it: dropped
+    let board = Board::from_fen_lenient("4k3/8/8/1P6/8/8/8/4K3 w - a6 0 1").unwrap();
+    assert_eq!(board.en_passant, None);
+    assert!(
+        board
+            .generate_legal_moves()
+            .iter()
+            .all(|m| !m.is_en_passant())
+    );
+    let board = Board::from_fen_lenient("4k3/8/8/pP6/8/8/8/4K3 w - a6 0 1").unwrap();
+    assert_eq!(board.en_passant, Some(A6));
+}
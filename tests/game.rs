@@ -0,0 +1,152 @@
+use my_own_chess_engine::*;
+
+fn mv(from: Square, to: Square) -> Move {
+    Move::new(from, to, Move::QUIET)
+}
+
+fn double_push(from: Square, to: Square) -> Move {
+    Move::new(from, to, Move::DOUBLE_PUSH)
+}
+
+fn status(fen: &str) -> GameStatus {
+    Game::from_board(Board::from_fen(fen).unwrap()).status()
+}
+
+#[test]
+fn detects_checkmate_and_stalemate() {
+    // Fool's mate
+    let mut game = Game::new();
+    for m in [
+        mv(F2, F3),
+        double_push(E7, E5),
+        double_push(G2, G4),
+        mv(D8, H4),
+    ] {
+        game.make_move(&m);
+    }
+    assert_eq!(
+        game.status(),
+        GameStatus::Checkmate {
+            winner: Color::Black
+        }
+    );
+    assert!(game.status().is_game_over());
+
+    assert_eq!(
+        status("7k/5Q2/6K1/8/8/8/8/8 b - - 0 1"),
+        GameStatus::Draw(DrawReason::Stalemate)
+    );
+}
+
+#[test]
+fn detects_insufficient_material() {
+    let draw = GameStatus::Draw(DrawReason::InsufficientMaterial);
+    assert_eq!(status("4k3/8/8/8/8/8/8/4K3 w - - 0 1"), draw);
+    assert_eq!(status("4k3/8/8/8/8/8/8/4KN2 w - - 0 1"), draw);
+    assert_eq!(status("4k3/8/8/8/8/8/8/4KB2 w - - 0 1"), draw);
+    // Bishops on the same square colour, however many
+    assert_eq!(status("4kb2/8/8/8/8/8/8/2B1K3 w - - 0 1"), draw);
+    assert_eq!(status("4k3/8/8/8/8/8/8/B1B1K3 w - - 0 1"), draw);
+
+    // Opposite-coloured bishops, knight pairs and pawns can still mate
+    assert_eq!(
+        status("4k3/5b2/8/8/8/8/8/2B1K3 w - - 0 1"),
+        GameStatus::Ongoing
+    );
+    assert_eq!(
+        status("4k3/8/8/8/8/8/8/1NN1K3 w - - 0 1"),
+        GameStatus::Ongoing
+    );
+    assert_eq!(
+        status("4kn2/8/8/8/8/8/8/2B1K3 w - - 0 1"),
+        GameStatus::Ongoing
+    );
+    assert_eq!(
+        status("4k3/8/8/8/8/8/4P3/4K3 w - - 0 1"),
+        GameStatus::Ongoing
+    );
+}
+
+#[test]
+fn detects_move_count_rules() {
+    assert_eq!(
+        status("4k3/8/8/8/8/8/8/R3K3 w - - 99 80"),
+        GameStatus::Ongoing
+    );
+    let fifty = status("4k3/8/8/8/8/8/8/R3K3 w - - 100 80");
+    assert_eq!(fifty, GameStatus::Draw(DrawReason::FiftyMoves));
+    assert!(!fifty.is_game_over());
+    assert_eq!(
+        status("4k3/8/8/8/8/8/8/R3K3 w - - 150 80"),
+        GameStatus::Draw(DrawReason::SeventyFiveMoves)
+    );
+    // Mate on the last move stands
+    assert_eq!(
+        status("R3k3/8/4K3/8/8/8/8/8 b - - 150 80"),
+        GameStatus::Checkmate {
+            winner: Color::White
+        }
+    );
+}
+
+#[test]
+fn detects_repetitions_and_takes_back() {
+    let mut game = Game::new();
+    let shuffle = [mv(G1, F3), mv(G8, F6), mv(F3, G1), mv(F6, G8)];
+
+    for _ in 0..2 {
+        for m in &shuffle {
+            assert_eq!(game.status(), GameStatus::Ongoing);
+            game.make_move(m);
+        }
+    }
+    assert_eq!(game.repetition_count(), 3);
+    assert_eq!(
+        game.status(),
+        GameStatus::Draw(DrawReason::ThreefoldRepetition)
+    );
+
+    for _ in 0..2 {
+        for m in &shuffle {
+            game.make_move(m);
+        }
+    }
+    assert_eq!(
+        game.status(),
+        GameStatus::Draw(DrawReason::FivefoldRepetition)
+    );
+
+    for _ in 0..8 {
+        game.take_back();
+    }
+    assert_eq!(game.repetition_count(), 3);
+    while game.take_back().is_some() {}
+    assert_eq!(*game.board(), Board::new());
+}
+
+#[test]
+fn pawn_move_resets_repetition_window() {
+    let mut game = Game::new();
+    let shuffle = [mv(G1, F3), mv(G8, F6), mv(F3, G1), mv(F6, G8)];
+    for m in &shuffle {
+        game.make_move(m);
+    }
+    game.make_move(&double_push(E2, E4));
+    game.make_move(&double_push(E7, E5));
+    for m in &shuffle {
+        game.make_move(m);
+    }
+    assert_eq!(game.repetition_count(), 2);
+}
+
+#[test]
+fn unusable_en_passant_square_does_not_break_repetition() {
+    // After 1. e4 the e3 square is set but Black cannot capture on it,
+    // so returning to the same position later is a repetition
+    let mut game = Game::new();
+    game.make_move(&double_push(E2, E4));
+    for m in [mv(G8, F6), mv(G1, F3), mv(F6, G8), mv(F3, G1)] {
+        game.make_move(&m);
+    }
+    assert_eq!(game.repetition_count(), 2);
+}
@@ -0,0 +1,48 @@
+use my_own_chess_engine::*;
+
+fn safety(fen: &str, color: Color) -> Score {
+    Board::from_fen(fen).unwrap().king_safety(color)
+}
+
+#[test]
+fn shelter_and_storm() {
+    let intact = safety("6k1/5ppp/8/8/8/8/5PPP/6K1 w - - 0 1", Color::White);
+    let advanced = safety("6k1/5ppp/8/8/5PPP/8/8/6K1 w - - 0 1", Color::White);
+    let bare = safety("6k1/5ppp/8/8/8/8/8/6K1 w - - 0 1", Color::White);
+    assert!(intact.mg > advanced.mg);
+    assert!(advanced.mg > bare.mg);
+
+    let stormed = safety("6k1/5pp1/8/8/8/6p1/5P1P/6K1 w - - 0 1", Color::White);
+    let calm = safety("6k1/5pp1/8/8/8/8/5P1P/6K1 w - - 0 1", Color::White);
+    assert!(stormed.mg < calm.mg);
+
+    // The king on the edge is judged by the three files nearest to it
+    assert_eq!(
+        safety("6k1/8/8/8/8/8/5PPP/7K w - - 0 1", Color::White),
+        safety("6k1/8/8/8/8/8/5PPP/6K1 w - - 0 1", Color::White)
+    );
+}
+
+#[test]
+fn attacks_on_the_king_zone() {
+    // Queen and knight both hit the squares around g1
+    let attacked = safety("6k1/5ppp/8/8/6nq/8/5PPP/6K1 w - - 0 1", Color::White);
+    let distant = safety("6k1/5ppp/8/nq6/8/8/5PPP/6K1 w - - 0 1", Color::White);
+    assert!(attacked.mg < distant.mg);
+    assert!(attacked.eg < distant.eg);
+
+    // The same attack counts for more with more material behind it
+    let backed = safety("r5k1/5ppp/8/8/6nq/8/5PPP/6K1 w - - 0 1", Color::White);
+    assert!(backed.mg < attacked.mg);
+
+    // With nothing left to attack with, an open king is no danger
+    let bare = safety("6k1/8/8/8/8/8/8/6K1 w - - 0 1", Color::White);
+    assert_eq!(bare.eg, 0);
+}
+
+#[test]
+fn both_kings_are_judged_alike() {
+    let white = safety("6k1/5ppp/8/8/6nq/8/5PPP/6K1 w - - 0 1", Color::White);
+    let black = safety("6k1/5ppp/8/6NQ/8/8/5PPP/6K1 b - - 0 1", Color::Black);
+    assert_eq!(white, black);
+}
@@ -0,0 +1,46 @@
+use my_own_chess_engine::*;
+
+fn key(m: &Move) -> u16 {
+    m.raw()
+}
+
+/// Walk the tree, checking at every node that the pin-aware generator
+/// produces exactly the moves that survive make-and-test filtering
+fn compare(board: &mut Board, depth: u32) {
+    let mut fast: Vec<_> = board.generate_legal_moves().iter().map(key).collect();
+    let mut reference: Vec<_> = board
+        .generate_legal_moves_by_filter()
+        .iter()
+        .map(key)
+        .collect();
+    fast.sort();
+    reference.sort();
+    assert_eq!(fast, reference, "move lists differ in {:?}", board);
+
+    if depth == 0 {
+        return;
+    }
+    for m in board.generate_legal_moves() {
+        let undo = board.make_move(&m);
+        compare(board, depth - 1);
+        board.unmake_move(&m, &undo);
+    }
+}
+
+#[test]
+fn pin_aware_generator_matches_filtered_generator() {
+    for fen in [
+        "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+        "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+        "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+        // En passant that would expose the king along the rank
+        "8/8/8/K2pP2r/8/8/8/7k w - d6 0 1",
+        // En passant capturing the checking pawn
+        "8/8/8/3pP3/4K3/8/8/7k w - d6 0 1",
+    ] {
+        let mut board = Board::from_fen(fen).unwrap();
+        compare(&mut board, 2);
+    }
+}
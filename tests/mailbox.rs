@@ -0,0 +1,68 @@
+use my_own_chess_engine::*;
+
+/// Every square's mailbox entry must agree with the bitboards
+fn assert_in_sync(board: &Board) {
+    for sq in Square::all() {
+        let mut from_bitboards = None;
+        for color in [Color::White, Color::Black] {
+            for pt in 0..6 {
+                if get_bit(board.pieces[color as usize][pt], sq) {
+                    from_bitboards = Some(Piece {
+                        color,
+                        piece_type: PieceType::from_usize(pt).unwrap(),
+                    });
+                }
+            }
+        }
+        assert_eq!(
+            board.piece_on(sq),
+            from_bitboards,
+            "square {} in {:?}",
+            sq,
+            board
+        );
+    }
+}
+
+fn walk(board: &mut Board, depth: u32) {
+    assert_in_sync(board);
+    if depth == 0 {
+        return;
+    }
+    for m in board.generate_legal_moves() {
+        let before = *board;
+        let undo = board.make_move(&m);
+        walk(board, depth - 1);
+        board.unmake_move(&m, &undo);
+        assert_eq!(*board, before);
+    }
+}
+
+#[test]
+fn piece_on_reads_the_position() {
+    let board = Board::new();
+    let piece = |color, piece_type| Some(Piece { color, piece_type });
+    assert_eq!(board.piece_on(E1), piece(Color::White, PieceType::King));
+    assert_eq!(board.piece_on(D8), piece(Color::Black, PieceType::Queen));
+    assert_eq!(board.piece_on(G2), piece(Color::White, PieceType::Pawn));
+    assert_eq!(board.piece_on(E4), None);
+
+    let board = Board::from_fen("4k3/8/8/8/8/8/8/R3K1n1 w Q - 0 1").unwrap();
+    assert_eq!(board.piece_on(A1), piece(Color::White, PieceType::Rook));
+    assert_eq!(board.piece_on(G1), piece(Color::Black, PieceType::Knight));
+    assert_in_sync(&board);
+}
+
+#[test]
+fn stays_in_sync_through_make_and_unmake() {
+    for fen in [
+        // Castling, promotions and captures of every kind
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+        // En passant
+        "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3",
+    ] {
+        let mut board = Board::from_fen(fen).unwrap();
+        walk(&mut board, 2);
+    }
+}
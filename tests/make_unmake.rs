@@ -0,0 +1,32 @@
+use my_own_chess_engine::*;
+
+/// Make and unmake every move down to `depth`, checking that each unmake
+/// puts back exactly the position that was there before
+fn walk(board: &mut Board, depth: u32) {
+    if depth == 0 {
+        return;
+    }
+    for m in board.generate_legal_moves() {
+        let before = *board;
+        let undo = board.make_move(&m);
+        walk(board, depth - 1);
+        board.unmake_move(&m, &undo);
+        assert_eq!(*board, before, "after unmaking {}", m.to_uci());
+    }
+}
+
+#[test]
+fn unmake_restores_the_position() {
+    for fen in [
+        // Castling, and captures of castling rooks
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        // Promotion captures on b1 and a8
+        "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+        // En passant, including one that would expose the king
+        "8/8/1k6/2b5/2pP4/8/5K2/8 b - d3 0 1",
+        "3k4/3p4/8/K1P4r/8/8/8/8 b - - 0 1",
+    ] {
+        let mut board = Board::from_fen(fen).unwrap();
+        walk(&mut board, 3);
+    }
+}
@@ -0,0 +1,109 @@
+use my_own_chess_engine::*;
+
+#[test]
+fn packs_into_sixteen_bits() {
+    assert_eq!(std::mem::size_of::<Move>(), 2);
+
+    let m = Move::new(E2, E4, Move::DOUBLE_PUSH);
+    assert_eq!((m.from(), m.to(), m.flags()), (E2, E4, Move::DOUBLE_PUSH));
+    assert_eq!(Move::from_raw(m.raw()), m);
+    assert!(m.is_double_push() && m.is_quiet() && !m.is_capture());
+
+    let m = Move::new_promotion(B7, A8, PieceType::Knight, true);
+    assert_eq!(m.promotion(), Some(PieceType::Knight));
+    assert!(m.is_capture() && m.is_promotion() && !m.is_quiet());
+    for piece in [
+        PieceType::Knight,
+        PieceType::Bishop,
+        PieceType::Rook,
+        PieceType::Queen,
+    ] {
+        let m = Move::new_promotion(E7, E8, piece, false);
+        assert_eq!(m.promotion(), Some(piece));
+        assert!(!m.is_capture());
+    }
+
+    let ep = Move::new(E5, D6, Move::EN_PASSANT);
+    assert!(ep.is_en_passant() && ep.is_capture() && ep.promotion().is_none());
+    assert!(Move::new(E1, G1, Move::KING_CASTLE).is_castling());
+    assert!(Move::new(E1, C1, Move::QUEEN_CASTLE).is_castling());
+    assert!(!Move::new(E1, F1, Move::QUIET).is_castling());
+}
+
+#[test]
+fn generated_flags_match_the_position() {
+    for fen in [
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+        "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3",
+    ] {
+        let board = Board::from_fen(fen).unwrap();
+        for m in board.generate_legal_moves() {
+            // Coordinates alone, re-encoded against the board, give back the same move
+            assert_eq!(board.encode_move(UnpackedMove::from(m)), m, "{}", fen);
+        }
+        let mut pseudo = board.generate_pseudo_moves();
+        let mut legal = board.generate_legal_moves_by_filter();
+        pseudo.retain(|m| legal.contains(m));
+        pseudo.sort_by_key(|m| m.raw());
+        legal.sort_by_key(|m| m.raw());
+        assert_eq!(pseudo, legal);
+    }
+}
+
+#[test]
+fn captured_piece_handles_en_passant() {
+    let board =
+        Board::from_fen("rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3").unwrap();
+    let ep = board.parse_uci_move("e5f6").unwrap();
+    assert!(ep.is_en_passant());
+    assert_eq!(ep.captured_piece(&board), Some(PieceType::Pawn));
+    assert_eq!(ep.to_long_algebraic(&board), "e5xf6");
+
+    let push = board.parse_uci_move("e5e6").unwrap();
+    assert_eq!(push.captured_piece(&board), None);
+    assert_eq!(push.to_long_algebraic(&board), "e5e6");
+}
+
+#[test]
+fn converts_from_unpacked_moves() {
+    // `Move::from` is the from-square accessor, so convert with `into`
+    let m: Move = UnpackedMove {
+        from: E7,
+        to: E8,
+        promotion: Some(PieceType::Queen),
+    }
+    .into();
+    assert_eq!(m, Move::new(E7, E8, Move::QUEEN_PROMOTION));
+    assert_eq!(
+        UnpackedMove::from(m),
+        UnpackedMove {
+            from: E7,
+            to: E8,
+            promotion: Some(PieceType::Queen),
+        }
+    );
+}
+
+#[test]
+fn encoded_coordinate_captures_remove_the_captured_piece() {
+    let mut board = Board::from_fen("4k3/8/8/3p4/4P3/8/8/4K3 w - - 0 1").unwrap();
+    let m = board.encode_move(UnpackedMove {
+        from: E4,
+        to: D5,
+        promotion: None,
+    });
+    assert!(m.is_capture());
+
+    board.apply_move(&m);
+    assert_eq!(
+        board.piece_on(D5),
+        Some(Piece {
+            color: Color::White,
+            piece_type: PieceType::Pawn,
+        })
+    );
+    assert!(board.pieces[Color::Black as usize][PieceType::Pawn as usize].is_empty());
+    assert_eq!(board.all_pieces(Color::Black).popcount(), 1);
+    assert_eq!(board.hash, board.compute_hash());
+}
@@ -0,0 +1,70 @@
+use my_own_chess_engine::*;
+
+#[test]
+fn push_index_and_iterate() {
+    let mut list = MoveList::new();
+    assert!(list.is_empty());
+    let a = Move::new(E2, E4, Move::DOUBLE_PUSH);
+    let b = Move::new(G1, F3, Move::QUIET);
+    list.push(a);
+    list.push(b);
+
+    assert_eq!(list.len(), 2);
+    assert_eq!(list[0], a);
+    assert_eq!(list[1], b);
+    assert!(list.contains(&b));
+    assert_eq!(list.iter().copied().collect::<Vec<_>>(), vec![a, b]);
+    assert_eq!(list.clone().into_iter().collect::<Vec<_>>(), vec![a, b]);
+
+    list.clear();
+    assert!(list.is_empty());
+}
+
+#[test]
+fn sorts_by_score_highest_first_and_stable() {
+    let board = Board::new();
+    let mut list = MoveList::new();
+    board.generate_legal_moves_into(&mut list);
+    let original = list.clone();
+
+    // Knight moves first, then pawn moves in generation order
+    list.sort_by_score(|m| {
+        if m.moving_piece(&board) == Some(PieceType::Knight) {
+            10
+        } else {
+            0
+        }
+    });
+    assert_eq!(list.len(), 20);
+    assert!(
+        list[..4]
+            .iter()
+            .all(|m| m.moving_piece(&board) == Some(PieceType::Knight))
+    );
+    let pawns: Vec<Move> = original
+        .iter()
+        .copied()
+        .filter(|m| m.moving_piece(&board) == Some(PieceType::Pawn))
+        .collect();
+    assert_eq!(&list[4..], pawns.as_slice());
+}
+
+#[test]
+fn list_generators_match_vec_generators() {
+    for fen in [
+        "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+    ] {
+        let board = Board::from_fen(fen).unwrap();
+
+        let mut list = MoveList::new();
+        board.generate_legal_moves_into(&mut list);
+        assert_eq!(list.as_slice(), board.generate_legal_moves().as_slice());
+
+        // The list variants append rather than overwrite
+        let legal = list.len();
+        board.generate_pseudo_moves_into(&mut list);
+        assert_eq!(&list[legal..], board.generate_pseudo_moves().as_slice());
+    }
+}
@@ -0,0 +1,98 @@
+use my_own_chess_engine::*;
+
+fn report(fen: &str) -> PawnReport {
+    Board::from_fen(fen).unwrap().pawn_report()
+}
+
+fn squares(list: &[Square]) -> Bitboard {
+    list.iter().copied().collect()
+}
+
+#[test]
+fn finds_each_feature() {
+    let r = report("4k3/8/8/8/8/2P5/2P5/4K3 w - - 0 1");
+    let white = Color::White;
+    assert_eq!(r.squares(white, PawnFeature::Doubled), C2.bit());
+    assert_eq!(r.squares(white, PawnFeature::Isolated), squares(&[C2, C3]));
+    assert_eq!(r.squares(white, PawnFeature::Passed), C3.bit());
+    assert!(r.structure.eg < 0);
+
+    let r = report("4k3/8/8/8/3PP3/2P5/8/4K3 w - - 0 1");
+    assert_eq!(r.squares(white, PawnFeature::Phalanx), squares(&[D4, E4]));
+    assert_eq!(r.squares(white, PawnFeature::Supported), D4.bit());
+    assert_eq!(r.squares(white, PawnFeature::Connected), squares(&[D4, E4]));
+    assert!(r.squares(white, PawnFeature::Isolated).is_empty());
+    assert_eq!(
+        r.features_on(D4),
+        [
+            PawnFeature::Phalanx,
+            PawnFeature::Supported,
+            PawnFeature::Connected,
+            PawnFeature::Passed
+        ]
+    );
+
+    // d3 cannot be supported, and e5 guards its stop square
+    let r = report("4k3/8/8/4p3/4P3/3P4/8/4K3 w - - 0 1");
+    assert_eq!(r.squares(white, PawnFeature::Backward), D3.bit());
+    assert_eq!(r.squares(white, PawnFeature::Supported), E4.bit());
+    assert!(r.squares(white, PawnFeature::Passed).is_empty());
+    assert!(r.squares(Color::Black, PawnFeature::Backward).is_empty());
+    assert_eq!(r.features_on(E5), [PawnFeature::Isolated]);
+    assert!(r.features_on(A1).is_empty());
+}
+
+#[test]
+fn passed_pawns_depend_on_blockers_and_kings() {
+    let far = report("k7/8/8/3P4/8/8/8/7K w - - 0 1");
+    let near = report("8/8/3k4/3P4/8/8/8/7K w - - 0 1");
+    assert_eq!(far.structure, near.structure);
+    assert!(far.passed_extras.eg > near.passed_extras.eg);
+    // In `near` the king also blocks the pawn
+    assert!(near.passed_extras.mg < 0);
+
+    // Mirrored, the score flips sign
+    let black = report("7k/8/8/8/3p4/8/8/K7 b - - 0 1");
+    assert_eq!(black.score(), -far.score());
+}
+
+#[test]
+fn pawn_key_ignores_pieces() {
+    let a = Board::from_fen("r3k3/pp6/8/8/8/8/PP6/4K2R w - - 0 1").unwrap();
+    let b = Board::from_fen("4k3/pp6/8/2n5/8/8/PP6/3QK3 b - - 0 1").unwrap();
+    let c = Board::from_fen("4k3/p7/1p6/8/8/8/PP6/4K3 w - - 0 1").unwrap();
+    assert_eq!(a.pawn_key, b.pawn_key);
+    assert_ne!(a.pawn_key, c.pawn_key);
+    assert_eq!(
+        Board::from_fen("4k3/8/8/8/8/8/8/4K3 w - - 0 1")
+            .unwrap()
+            .pawn_key,
+        0
+    );
+}
+
+#[test]
+fn table_matches_direct_evaluation() {
+    fn walk(board: &mut Board, depth: u32, table: &mut PawnTable) {
+        assert_eq!(board.pawn_key, board.compute_pawn_key());
+        assert_eq!(board.evaluate_with(table), board.evaluate());
+        if depth == 0 {
+            return;
+        }
+        for m in board.generate_legal_moves() {
+            let undo = board.make_move(&m);
+            walk(board, depth - 1, table);
+            board.unmake_move(&m, &undo);
+        }
+    }
+    // A tiny table, so that slots are shared and replaced
+    let mut table = PawnTable::new(16);
+    for fen in [
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+    ] {
+        walk(&mut Board::from_fen(fen).unwrap(), 3, &mut table);
+    }
+    table.clear();
+    assert_eq!(table.probe(&Board::new()), table.probe(&Board::new()));
+}
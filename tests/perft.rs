@@ -0,0 +1,114 @@
+use my_own_chess_engine::*;
+
+/// Published node counts from the Chess Programming Wiki perft results
+/// and the commonly used edge-case collection.
+fn check(fen: &str, depth: u32, expected: u64) {
+    let board = Board::from_fen(fen).unwrap();
+    assert_eq!(
+        board.perft_bulk(depth),
+        expected,
+        "{} at depth {}",
+        fen,
+        depth
+    );
+}
+
+#[test]
+fn start_position() {
+    let board = Board::new();
+    for (depth, expected) in [(0, 1), (1, 20), (2, 400), (3, 8_902)] {
+        assert_eq!(board.perft(depth), expected);
+        assert_eq!(board.perft_bulk(depth), expected);
+    }
+    assert_eq!(board.perft_bulk(5), 4_865_609);
+}
+
+#[test]
+fn kiwipete() {
+    let fen = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1";
+    check(fen, 1, 48);
+    check(fen, 2, 2_039);
+    check(fen, 3, 97_862);
+    check(fen, 4, 4_085_603);
+}
+
+#[test]
+fn position_3() {
+    check("8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1", 6, 11_030_083);
+}
+
+#[test]
+fn position_4() {
+    check(
+        "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+        5,
+        15_833_292,
+    );
+    // Mirrored
+    check(
+        "r2q1rk1/pP1p2pp/Q4n2/bbp1p3/Np6/1B3NBn/pPPP1PPP/R3K2R b KQ - 0 1",
+        4,
+        422_333,
+    );
+}
+
+#[test]
+fn position_5() {
+    check(
+        "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+        4,
+        2_103_487,
+    );
+}
+
+#[test]
+fn position_6() {
+    check(
+        "r4rk1/1pp1qppp/p1np1n2/2b1p1B1/2B1P1b1/P1NP1N2/1PP1QPPP/R4RK1 w - - 0 10",
+        4,
+        3_894_594,
+    );
+}
+
+#[test]
+fn en_passant_edge_cases() {
+    // Illegal en passant: both pawns leave the rank and expose the king
+    check("3k4/3p4/8/K1P4r/8/8/8/8 b - - 0 1", 6, 1_134_888);
+    check("8/8/8/8/k1p4R/8/3P4/3K4 w - - 0 1", 6, 1_134_888);
+    // En passant capture resolves a check
+    check("8/8/4k3/8/2p5/8/B2P2K1/8 w - - 0 1", 6, 1_015_133);
+    check("8/8/1k6/2b5/2pP4/8/5K2/8 b - d3 0 1", 6, 1_440_467);
+}
+
+#[test]
+fn castling_edge_cases() {
+    check("5k2/8/8/8/8/8/8/4K2R w K - 0 1", 6, 661_072);
+    check("3k4/8/8/8/8/8/8/R3K3 w Q - 0 1", 6, 803_711);
+    check("r3k2r/1b4bq/8/8/8/8/7B/R3K2R w KQkq - 0 1", 4, 1_274_206);
+    check("r3k2r/8/3Q4/8/8/5q2/8/R3K2R b KQkq - 0 1", 4, 1_720_476);
+}
+
+#[test]
+fn promotion_edge_cases() {
+    check("2K2r2/4P3/8/8/8/8/8/3k4 w - - 0 1", 6, 3_821_001);
+    check("4k3/1P6/8/8/8/8/K7/8 w - - 0 1", 6, 217_342);
+    check("8/P1k5/K7/8/8/8/8/8 w - - 0 1", 6, 92_683);
+    check("K1k5/8/P7/8/8/8/8/8 w - - 0 1", 6, 2_217);
+    check("8/k1P5/8/1K6/8/8/8/8 w - - 0 1", 7, 567_584);
+}
+
+#[test]
+fn discovered_and_double_check() {
+    check("8/8/1P2K3/8/2n5/1q6/8/5k2 b - - 0 1", 5, 1_004_658);
+    check("8/8/2k5/5q2/5n2/8/5K2/8 b - - 0 1", 4, 23_527);
+}
+
+#[test]
+fn divide_sums_to_perft() {
+    let board =
+        Board::from_fen("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1")
+            .unwrap();
+    let split = board.divide(3);
+    assert_eq!(split.len(), 48);
+    assert_eq!(split.iter().map(|(_, n)| n).sum::<u64>(), 97_862);
+}
@@ -0,0 +1,178 @@
+use my_own_chess_engine::*;
+
+fn read_all(pgn: &str) -> Vec<Result<PgnGame, PgnError>> {
+    PgnReader::new(pgn.as_bytes()).collect()
+}
+
+const TWO_GAMES: &str = r#"[Event "Casual \"blitz\""]
+[Site "?"]
+[Date "2024.01.01"]
+[Round "1"]
+[White "Alice"]
+[Black "Bob"]
+[Result "1-0"]
+[ECO "C20"]
+
+{Start} 1. e4 e5 2. Qh5?! {Dubious} Nc6 (2... g6 3. Qf3 (3. Qxe5+ $4) Nf6) 3. Bc4
+Nf6?? $4 ; overlooks mate
+4. Qxf7# 1-0
+
+% a line for some other program
+[Event "Second"]
+[SetUp "1"]
+[FEN "4k3/8/8/8/8/8/8/4K2R w K - 0 1"]
+[Result "*"]
+
+1. O-O Kd7 2. Rd1+ *
+"#;
+
+#[test]
+fn reads_tags_moves_and_annotations() {
+    let games = read_all(TWO_GAMES);
+    assert_eq!(games.len(), 2);
+
+    let game = games[0].as_ref().unwrap();
+    assert_eq!(game.tag("Event"), Some("Casual \"blitz\""));
+    assert_eq!(game.tag("ECO"), Some("C20"));
+    assert_eq!(game.tags.len(), 8);
+    assert_eq!(game.result, GameResult::WhiteWins);
+    assert_eq!(game.start, Board::new());
+
+    let uci: Vec<String> = game.mainline().map(|m| m.to_uci()).collect();
+    assert_eq!(
+        uci,
+        ["e2e4", "e7e5", "d1h5", "b8c6", "f1c4", "g8f6", "h5f7"]
+    );
+    assert!(game.to_game().board().is_checkmate());
+
+    assert_eq!(game.moves[0].comments_before, ["Start"]);
+    assert_eq!(game.moves[2].nags, [6]);
+    assert_eq!(game.moves[2].comments, ["Dubious"]);
+    assert_eq!(game.moves[5].nags, [4, 4]);
+    assert_eq!(game.moves[5].comments, ["overlooks mate"]);
+
+    // The variation replaces 2... Nc6 and nests one of its own
+    let variation = &game.moves[3].variations[0];
+    let uci: Vec<String> = variation.iter().map(|m| m.mv.to_uci()).collect();
+    assert_eq!(uci, ["g7g6", "h5f3", "g8f6"]);
+    assert_eq!(variation[1].variations[0][0].mv.to_uci(), "h5e5");
+    assert_eq!(variation[1].variations[0][0].nags, [4]);
+}
+
+#[test]
+fn starts_from_the_fen_tag() {
+    let games = read_all(TWO_GAMES);
+    let game = games[1].as_ref().unwrap();
+    assert_eq!(game.tag("SetUp"), Some("1"));
+    assert_eq!(game.start.to_fen(), "4k3/8/8/8/8/8/8/4K2R w K - 0 1");
+    assert_eq!(game.result, GameResult::Unknown);
+    let uci: Vec<String> = game.mainline().map(|m| m.to_uci()).collect();
+    assert_eq!(uci, ["e1g1", "e8d7", "f1d1"]);
+}
+
+#[test]
+fn reports_errors_with_line_and_column_and_recovers() {
+    let pgn = "[Event \"Bad\"]\n\n1. e4 e5\n2. Ke3 Nc6 *\n\n[Event \"Good\"]\n\n1. d4 *\n";
+    let games = read_all(pgn);
+    assert_eq!(games.len(), 2);
+    let err = games[0].as_ref().unwrap_err();
+    assert_eq!((err.line, err.column), (4, 4));
+    assert!(matches!(
+        err.kind,
+        PgnErrorKind::InvalidMove(SanError::Illegal(_))
+    ));
+    assert_eq!(games[1].as_ref().unwrap().tag("Event"), Some("Good"));
+
+    let err = read_all("[Event \"x\"]\n\n1. e4 (1. d4 d5\n")[0]
+        .clone()
+        .unwrap_err();
+    assert_eq!(err.kind, PgnErrorKind::UnterminatedVariation);
+
+    let err = read_all("[FEN \"8/8/8/8/8/8/8/8 w - - 0 1\"]\n*\n")[0]
+        .clone()
+        .unwrap_err();
+    assert_eq!((err.line, err.column), (1, 1));
+    assert!(matches!(err.kind, PgnErrorKind::InvalidFen(_)));
+
+    let err = read_all("1. e4 {never closed\n")[0].clone().unwrap_err();
+    assert_eq!((err.line, err.column), (1, 7));
+    assert_eq!(err.kind, PgnErrorKind::Unterminated);
+}
+
+#[test]
+fn games_without_tags_or_result() {
+    let games = read_all("1. e4 e5\n[Event \"Next\"]\n1. d4\n");
+    assert_eq!(games.len(), 2);
+    let first = games[0].as_ref().unwrap();
+    assert!(first.tags.is_empty());
+    assert_eq!(first.moves.len(), 2);
+    assert_eq!(first.result, GameResult::Unknown);
+    assert_eq!(games[1].as_ref().unwrap().moves.len(), 1);
+}
+
+#[test]
+fn writes_tags_numbers_and_annotations() {
+    let game = read_all(TWO_GAMES).remove(0).unwrap();
+    let pgn = game.to_pgn();
+    let expected = r#"[Event "Casual \"blitz\""]
+[Site "?"]
+[Date "2024.01.01"]
+[Round "1"]
+[White "Alice"]
+[Black "Bob"]
+[Result "1-0"]
+[ECO "C20"]
+
+{Start} 1. e4 e5 2. Qh5 $6 {Dubious} 2... Nc6 (2... g6 3. Qf3 (3. Qxe5+ $4) 3...
+Nf6) 3. Bc4 Nf6 $4 $4 {overlooks mate} 4. Qxf7# 1-0
+"#;
+    assert_eq!(pgn, expected);
+
+    // What is written reads back as the same game
+    let again = read_all(&pgn).remove(0).unwrap();
+    assert_eq!(again, game);
+}
+
+#[test]
+fn writes_setup_and_fen_for_other_start_positions() {
+    let board = Board::from_fen("4k3/8/8/8/8/8/8/4K2R b K - 0 7").unwrap();
+    let mut game = Game::from_board(board);
+    game.make_move(&board.parse_san("Kd7").unwrap());
+    let mut pgn = PgnGame::from_game(&game);
+    pgn.set_tag("White", "Engine");
+
+    let text = pgn.to_pgn();
+    assert!(text.starts_with("[Event \"?\"]\n[Site \"?\"]\n[Date \"????.??.??\"]\n"));
+    assert!(text.contains(
+        "[White \"Engine\"]\n[Black \"?\"]\n[Result \"*\"]\n[SetUp \"1\"]\n\
+         [FEN \"4k3/8/8/8/8/8/8/4K2R b K - 0 7\"]\n\n7... Kd7 *\n"
+    ));
+    let again = read_all(&text).remove(0).unwrap();
+    assert_eq!((again.start, again.moves), (pgn.start, pgn.moves));
+
+    // No SetUp or FEN from the standard position
+    assert!(!PgnGame::new(Board::new()).to_pgn().contains("FEN"));
+}
+
+#[test]
+fn wraps_long_movetext_at_80_columns() {
+    let mut game = Game::new();
+    for _ in 0..30 {
+        for san in ["Nf3", "Nf6", "Ng1", "Ng8"] {
+            let m = game.board().parse_san(san).unwrap();
+            game.make_move(&m);
+        }
+    }
+    let mut pgn = PgnGame::from_game(&game);
+    pgn.moves[0]
+        .comments
+        .push(["a comment long enough to be split over lines"; 3].join(" "));
+    assert_eq!(pgn.result, GameResult::Draw); // fivefold repetition
+
+    let text = pgn.to_pgn();
+    let movetext = text.split("\n\n").nth(1).unwrap();
+    assert!(movetext.lines().count() > 5);
+    assert!(movetext.lines().all(|line| line.len() <= 80));
+    assert!(movetext.trim_end().ends_with("1/2-1/2"));
+    assert_eq!(read_all(&text).remove(0).unwrap().moves, pgn.moves);
+}
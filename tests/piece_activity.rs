@@ -0,0 +1,80 @@
+use my_own_chess_engine::*;
+
+fn activity(fen: &str, color: Color) -> PieceActivity {
+    Board::from_fen(fen).unwrap().piece_activity(color)
+}
+
+#[test]
+fn mobility_counts_safe_squares() {
+    let center = activity("4k3/8/8/8/3N4/8/8/4K3 w - - 0 1", Color::White);
+    let rim = activity("4k3/8/8/8/8/8/8/N3K3 w - - 0 1", Color::White);
+    assert!(center.mobility[0].mg > rim.mobility[0].mg);
+    assert_eq!(center.mobility[1], Score::ZERO);
+
+    // Black pawns guard b5, c6, e6 and f5
+    let guarded = activity("4k3/3p4/2p3p1/8/3N4/8/8/4K3 w - - 0 1", Color::White);
+    assert!(guarded.mobility[0].mg < center.mobility[0].mg);
+}
+
+#[test]
+fn outposts_and_rook_files() {
+    let outpost = activity("4k3/8/8/3N4/4P3/8/8/4K3 w - - 0 1", Color::White);
+    assert!(outpost.outposts.mg > 0);
+    // A c-pawn could still chase the knight away
+    let chased = activity("4k3/2p5/8/3N4/4P3/8/8/4K3 w - - 0 1", Color::White);
+    assert_eq!(chased.outposts, Score::ZERO);
+
+    let rooks = activity("4k3/p1p5/8/8/8/8/1P6/R1R1K3 w - - 0 1", Color::White);
+    assert!(rooks.half_open_files.mg > 0); // a-file: only a Black pawn
+    assert!(rooks.open_files.mg == 0); // c-file has a Black pawn too
+    let open = activity("4k3/8/8/8/8/8/8/3RK3 w - - 0 1", Color::White);
+    assert!(open.open_files.mg > 0 && open.half_open_files == Score::ZERO);
+
+    let seventh = activity("6k1/R7/8/8/8/8/8/4K3 w - - 0 1", Color::White);
+    assert!(seventh.seventh_rank.eg > 0);
+    let no_target = activity("8/R7/6k1/8/8/8/8/4K3 w - - 0 1", Color::White);
+    assert_eq!(no_target.seventh_rank, Score::ZERO);
+}
+
+#[test]
+fn bishops_pairs_and_trapped_pieces() {
+    let pair = activity("4k3/8/8/8/8/8/8/2B1KB2 w - - 0 1", Color::White);
+    let same_color = activity("4k3/8/8/8/8/B7/8/2B1K3 w - - 0 1", Color::White);
+    assert!(pair.bishop_pair.eg > 0);
+    assert_eq!(same_color.bishop_pair, Score::ZERO);
+
+    let trapped = activity("4k3/B7/1p6/8/8/8/8/4K3 w - - 0 1", Color::White);
+    assert!(trapped.trapped.mg < 0);
+    let free = activity("4k3/B7/8/8/8/8/8/4K3 w - - 0 1", Color::White);
+    assert_eq!(free.trapped, Score::ZERO);
+
+    // The king walked to f1 and lost the right to castle
+    let shut_in = activity("4k3/8/8/8/8/8/5PPP/5K1R w - - 0 1", Color::White);
+    assert!(shut_in.trapped.mg < 0);
+    let can_castle = activity("4k3/8/8/8/8/8/5PPP/4K2R w K - 0 1", Color::White);
+    assert_eq!(can_castle.trapped, Score::ZERO);
+
+    // Light-squared bishop behind its own pawns on light squares
+    let bad = activity("4k3/8/8/2p1p3/2P1P3/3P4/8/4KB2 w - - 0 1", Color::White);
+    let good = activity("4k3/8/8/8/8/8/8/4KB2 w - - 0 1", Color::White);
+    assert!(bad.bad_bishops.eg < good.bad_bishops.eg);
+}
+
+#[test]
+fn terms_add_up_and_mirror() {
+    let fen = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1";
+    let a = activity(fen, Color::White);
+    let sum = a.mobility.iter().fold(Score::ZERO, |s, &m| s + m)
+        + a.outposts
+        + a.open_files
+        + a.half_open_files
+        + a.seventh_rank
+        + a.bishop_pair
+        + a.trapped
+        + a.bad_bishops;
+    assert_eq!(a.total(), sum);
+
+    let white = activity("4k3/8/8/3N4/4P3/8/8/R3K3 w - - 0 1", Color::White);
+    let black = activity("r3k3/8/8/4p3/3n4/8/8/4K3 b - - 0 1", Color::Black);
+    assert_eq!(white, black);
+}
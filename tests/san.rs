@@ -0,0 +1,129 @@
+use my_own_chess_engine::*;
+
+fn san(fen: &str, from: Square, to: Square, promotion: Option<PieceType>) -> String {
+    let board = Board::from_fen(fen).unwrap();
+    board
+        .encode_move(UnpackedMove {
+            from,
+            to,
+            promotion,
+        })
+        .to_san(&board)
+}
+
+#[test]
+fn writes_basic_moves() {
+    let start = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+    assert_eq!(san(start, E2, E4, None), "e4");
+    assert_eq!(san(start, G1, F3, None), "Nf3");
+
+    let fen = "rnbqkbnr/ppp1pppp/8/3p4/4P3/8/PPPP1PPP/RNBQKBNR w KQkq d6 0 2";
+    assert_eq!(san(fen, E4, D5, None), "exd5");
+    // En passant is a capture even though d6 is empty
+    let fen = "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3";
+    assert_eq!(san(fen, E5, F6, None), "exf6");
+}
+
+#[test]
+fn writes_castling_promotion_and_suffixes() {
+    let fen = "r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1";
+    assert_eq!(san(fen, E1, G1, None), "O-O");
+    assert_eq!(san(fen, E1, C1, None), "O-O-O");
+
+    let fen = "k2r4/4P3/8/8/8/8/8/2K5 w - - 0 1";
+    assert_eq!(san(fen, E7, E8, Some(PieceType::Queen)), "e8=Q");
+    assert_eq!(san(fen, E7, D8, Some(PieceType::Knight)), "exd8=N");
+    assert_eq!(san(fen, E7, D8, Some(PieceType::Queen)), "exd8=Q+");
+
+    // Back-rank mate
+    assert_eq!(
+        san("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1", A1, A8, None),
+        "Ra8#"
+    );
+}
+
+#[test]
+fn disambiguates_by_file_rank_or_both() {
+    // Knights on b8 and f6 can both reach d7
+    let fen = "1n2k3/8/5n2/8/8/8/8/4K3 b - - 0 1";
+    assert_eq!(san(fen, B8, D7, None), "Nbd7");
+    // Rooks on a1 and a5 share a file
+    let fen = "4k3/8/8/R7/8/8/8/R3K3 w - - 0 1";
+    assert_eq!(san(fen, A1, A3, None), "R1a3");
+    // Queens on e4, h4 and h1 all reach e1
+    let fen = "1k6/8/8/8/4Q2Q/8/8/2K4Q w - - 0 1";
+    assert_eq!(san(fen, H4, E1, None), "Qh4e1");
+    // A pinned rival does not need disambiguating
+    let fen = "4k3/8/8/8/8/b7/1N6/2K1N3 w - - 0 1";
+    assert_eq!(san(fen, E1, D3, None), "Nd3");
+}
+
+#[test]
+fn parses_strict_and_loose_forms() {
+    let board = Board::from_fen("r3k2r/1P6/8/3pP3/8/8/8/R3K2R w KQkq d6 0 1").unwrap();
+    let parse = |s: &str| board.parse_san(s).unwrap();
+
+    assert_eq!((parse("O-O").from(), parse("O-O").to()), (E1, G1));
+    assert_eq!(parse("0-0-0").to(), C1);
+    assert_eq!(parse("exd6").to(), D6);
+    assert_eq!(parse("ed6").to(), D6);
+    assert_eq!(parse("e6").to(), E6);
+    assert_eq!(parse("Pe6").to(), E6);
+    assert_eq!(parse("b8=Q").promotion(), Some(PieceType::Queen));
+    assert_eq!(parse("b8Q").promotion(), Some(PieceType::Queen));
+    assert_eq!(parse("b8n").promotion(), Some(PieceType::Knight));
+    assert_eq!(parse("bxa8=R+").promotion(), Some(PieceType::Rook));
+    assert_eq!(parse("Rxa8+!?").from(), A1);
+    assert_eq!(parse("Ra1xa8").from(), A1);
+    assert_eq!(parse("Rh1-h7").to(), H7);
+
+    let knights = Board::from_fen("1n2k3/8/5n2/8/8/8/8/4K3 b - - 0 1").unwrap();
+    assert_eq!(knights.parse_san("Nbd7").unwrap().from(), B8);
+    assert_eq!(knights.parse_san("Nfd7").unwrap().from(), F6);
+}
+
+#[test]
+fn rejects_bad_input_clearly() {
+    let board = Board::from_fen("1n2k3/8/5n2/8/8/8/8/4K3 b - - 0 1").unwrap();
+    assert!(matches!(
+        board.parse_san("Nd7"),
+        Err(SanError::Ambiguous { ref candidates, .. }) if candidates.len() == 2
+    ));
+    assert_eq!(
+        board.parse_san("Nd6"),
+        Err(SanError::Illegal("Nd6".to_string()))
+    );
+    assert_eq!(
+        board.parse_san("O-O"),
+        Err(SanError::Illegal("O-O".to_string()))
+    );
+    assert_eq!(
+        board.parse_san("Zz9"),
+        Err(SanError::Malformed("Zz9".to_string()))
+    );
+    assert_eq!(
+        board.parse_san(""),
+        Err(SanError::Malformed("".to_string()))
+    );
+
+    // Promotion piece is required
+    let board = Board::from_fen("4k3/1P6/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+    assert_eq!(
+        board.parse_san("b8"),
+        Err(SanError::Illegal("b8".to_string()))
+    );
+}
+
+#[test]
+fn round_trips_every_legal_move() {
+    for fen in [
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+        "1k6/8/8/8/4Q2Q/8/8/2K4Q w - - 0 1",
+    ] {
+        let board = Board::from_fen(fen).unwrap();
+        for m in board.generate_legal_moves() {
+            assert_eq!(board.parse_san(&m.to_san(&board)), Ok(m), "{}", fen);
+        }
+    }
+}
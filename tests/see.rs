@@ -0,0 +1,102 @@
+use my_own_chess_engine::*;
+
+fn see(fen: &str, uci: &str) -> i32 {
+    let board = Board::from_fen(fen).unwrap();
+    board.see(board.parse_uci_move(uci).unwrap())
+}
+
+const PAWN: i32 = SEE_VALUES[0];
+const KNIGHT: i32 = SEE_VALUES[1];
+const ROOK: i32 = SEE_VALUES[3];
+const QUEEN: i32 = SEE_VALUES[4];
+
+#[test]
+fn plain_exchanges() {
+    // Undefended pawn
+    assert_eq!(
+        see("1k1r4/1pp4p/p7/4p3/8/P5P1/1PP4P/2K1R3 w - - 0 1", "e1e5"),
+        PAWN
+    );
+    // Pawn defended by a pawn, taken by the queen
+    assert_eq!(
+        see("4k3/8/3p4/4p3/8/8/8/4QK2 w - - 0 1", "e1e5"),
+        PAWN - QUEEN
+    );
+    // Knight for pawn: carrying on after the recapture only loses more
+    assert_eq!(
+        see(
+            "1k1r3q/1ppn3p/p4b2/4p3/8/P2N2P1/1PP1R1BP/2K1Q3 w - - 0 1",
+            "d3e5"
+        ),
+        PAWN - KNIGHT
+    );
+    // A quiet move to a square nothing attacks neither wins nor loses
+    assert_eq!(see("4k3/8/8/3p4/8/8/8/2N1K3 w - - 0 1", "c1e2"), 0);
+    // A quiet move to an attacked square just loses the piece
+    assert_eq!(see("4k3/8/8/8/3p4/8/8/3NK3 w - - 0 1", "d1e3"), -KNIGHT);
+}
+
+#[test]
+fn x_rays_join_the_exchange() {
+    // The rook on e1 backs up the one on e2 once it has captured
+    assert_eq!(see("4r1k1/8/8/4p3/8/8/4R3/4R1K1 w - - 0 1", "e2e5"), PAWN);
+    // Without it, the rook is lost for a pawn
+    assert_eq!(
+        see("4r1k1/8/8/4p3/8/8/4R3/6K1 w - - 0 1", "e2e5"),
+        PAWN - ROOK
+    );
+    // A queen behind a bishop on the diagonal
+    assert_eq!(see("4k3/8/5b2/4p3/3B4/2Q5/8/4K3 w - - 0 1", "d4e5"), PAWN);
+    assert_eq!(
+        see("4k3/8/5b2/4p3/3B4/8/8/4K3 w - - 0 1", "d4e5"),
+        PAWN - SEE_VALUES[2]
+    );
+
+    // The king only recaptures when nothing defends the square
+    assert_eq!(see("3rk3/8/8/8/8/8/3P4/4K3 b - - 0 1", "d8d2"), PAWN - ROOK);
+    assert_eq!(see("3qk3/3r4/8/8/8/8/3P4/4K3 b - - 0 1", "d7d2"), PAWN);
+}
+
+#[test]
+fn special_moves() {
+    assert_eq!(see("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1", "e5d6"), PAWN);
+    assert_eq!(
+        see("4k3/1P6/8/8/8/8/8/4K3 w - - 0 1", "b7b8q"),
+        QUEEN - PAWN
+    );
+    // The new queen is taken at once: only the pawn is lost
+    assert_eq!(see("r3k3/1P6/8/8/8/8/8/4K3 w - - 0 1", "b7b8q"), -PAWN);
+    assert_eq!(
+        see("r3k3/1P6/8/8/8/8/8/4K3 w - - 0 1", "b7a8q"),
+        ROOK + QUEEN - PAWN
+    );
+    assert_eq!(see("4k3/8/8/8/8/8/8/4K2R w K - 0 1", "e1g1"), 0);
+
+    let board = Board::from_fen("4k3/8/3p4/4p3/8/8/8/4QK2 w - - 0 1").unwrap();
+    let m = board.parse_uci_move("e1e5").unwrap();
+    assert!(board.see_ge(m, PAWN - QUEEN));
+    assert!(!board.see_ge(m, PAWN - QUEEN + 1));
+    assert!(!board.see_ge(m, 0));
+}
+
+#[test]
+fn attackers_of_both_colors() {
+    let board = Board::from_fen("4k3/8/3p4/4p3/3N4/8/8/4RK2 w - - 0 1").unwrap();
+    let attackers = board.attackers_to(E5, board.occupied);
+    assert_eq!(attackers, D6.bit() | E1.bit());
+    // Removing a piece from the occupancy takes it out and opens lines behind it
+    let attackers = board.attackers_to(E4, board.occupied & !E5.bit());
+    assert_eq!(attackers, E1.bit());
+    let attackers = board.attackers_to(C6, board.occupied);
+    assert_eq!(attackers, D4.bit());
+
+    for sq in Square::all() {
+        for color in [Color::White, Color::Black] {
+            assert_eq!(
+                !(board.attackers_to(sq, board.occupied) & board.all_pieces(color)).is_empty(),
+                board.is_square_attacked(sq, color),
+                "{sq} {color:?}"
+            );
+        }
+    }
+}
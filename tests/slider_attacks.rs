@@ -0,0 +1,35 @@
+use my_own_chess_engine::*;
+
+/// Small deterministic generator so failures are reproducible
+fn next(state: &mut u64) -> u64 {
+    *state ^= *state << 13;
+    *state ^= *state >> 7;
+    *state ^= *state << 17;
+    *state
+}
+
+#[test]
+fn magic_attacks_match_ray_reference() {
+    let mut state = 0x9E37_79B9_7F4A_7C15;
+    for sq in Square::all() {
+        for _ in 0..500 {
+            // Mix of sparse and dense occupancies
+            let occupied = Bitboard(next(&mut state) & next(&mut state));
+            assert_eq!(
+                bishop_attacks(sq, occupied),
+                bishop_attacks_ray(sq, occupied),
+                "bishop on {sq} with occupancy {occupied:?}"
+            );
+            assert_eq!(
+                rook_attacks(sq, occupied),
+                rook_attacks_ray(sq, occupied),
+                "rook on {sq} with occupancy {occupied:?}"
+            );
+            assert_eq!(
+                queen_attacks(sq, occupied),
+                queen_attacks_ray(sq, occupied),
+                "queen on {sq} with occupancy {occupied:?}"
+            );
+        }
+    }
+}
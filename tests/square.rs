@@ -0,0 +1,54 @@
+use my_own_chess_engine::*;
+
+#[test]
+fn parses_and_displays() {
+    assert_eq!("e4".parse::<Square>(), Ok(E4));
+    assert_eq!("a1".parse::<Square>(), Ok(A1));
+    assert_eq!("h8".parse::<Square>(), Ok(H8));
+    for bad in ["", "e", "e9", "i1", "E4", "e44", "4e"] {
+        assert_eq!(bad.parse::<Square>(), Err(ParseSquareError), "{:?}", bad);
+    }
+
+    assert_eq!(E4.to_string(), "e4");
+    assert_eq!(format!("{:?}", H8), "h8");
+    for sq in Square::all() {
+        assert_eq!(sq.to_string().parse::<Square>(), Ok(sq));
+    }
+}
+
+#[test]
+fn files_ranks_and_raw_indices() {
+    assert_eq!(E4.file(), File::E);
+    assert_eq!(E4.rank(), Rank::FOUR);
+    assert_eq!(Square::from_coords(File::C, Rank::SEVEN), C7);
+    assert_eq!(File::new(8), None);
+    assert_eq!(Rank::from_char('9'), None);
+    assert_eq!(File::from_char('g'), Some(File::G));
+
+    assert_eq!(Square::new(28), Some(E4));
+    assert_eq!(Square::new(64), None);
+    assert_eq!(Square::try_from(63u8), Ok(H8));
+    assert!(Square::try_from(200u8).is_err());
+    assert_eq!(u8::from(E4), 28);
+    assert_eq!(E4.index(), 28);
+    assert_eq!(Square::all().count(), 64);
+}
+
+#[test]
+fn offsets_stay_on_the_board() {
+    assert_eq!(E4.offset(1, 2), Some(F6));
+    assert_eq!(E4.offset(-4, -3), Some(A1));
+    assert_eq!(A1.offset(-1, 0), None);
+    assert_eq!(H4.offset(1, 0), None); // no wrap onto the next rank
+    assert_eq!(A5.offset(-1, 1), None);
+    assert_eq!(E8.offset(0, 1), None);
+    assert_eq!(E1.offset(0, -1), None);
+
+    assert_eq!(E2.flip(), E7);
+    assert_eq!(A1.flip(), A8);
+    assert_eq!(Rank::TWO.flip(), Rank::SEVEN);
+    assert_eq!(A1.distance(H8), 7);
+    assert_eq!(E4.distance(F6), 2);
+    assert_eq!(E4.distance(E4), 0);
+    assert_eq!(E4.bit(), Bitboard(1u64 << 28));
+}
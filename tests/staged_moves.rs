@@ -0,0 +1,149 @@
+use my_own_chess_engine::*;
+
+const FENS: [&str; 9] = [
+    "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+    "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+    "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+    "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+    "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+    // Castling with check
+    "5k2/8/8/8/8/8/8/4K2R w K - 0 1",
+    // Promotions that check, one of them through the square the pawn left
+    "r3k3/1P6/8/8/8/8/8/4K3 w - - 0 1",
+    "r7/1P6/8/3k4/8/8/8/4K3 w - - 0 1",
+    // En passant that discovers a check on the rank
+    "8/8/8/k2pP2R/8/8/8/4K3 w - d6 0 1",
+];
+
+fn sorted(moves: &[Move]) -> Vec<u16> {
+    let mut keys: Vec<u16> = moves.iter().map(|m| m.raw()).collect();
+    keys.sort();
+    keys
+}
+
+fn gives_check_by_playing(board: &Board, m: Move) -> bool {
+    let mut after = *board;
+    after.apply_move(&m);
+    after.is_in_check(after.turn)
+}
+
+/// Run `check` on every position up to `depth` plies from each test position
+fn for_each_position(depth: u32, check: &mut impl FnMut(&Board)) {
+    fn walk(board: &mut Board, depth: u32, check: &mut impl FnMut(&Board)) {
+        check(board);
+        if depth == 0 {
+            return;
+        }
+        for m in board.generate_legal_moves() {
+            let undo = board.make_move(&m);
+            walk(board, depth - 1, check);
+            board.unmake_move(&m, &undo);
+        }
+    }
+    for fen in FENS {
+        walk(&mut Board::from_fen(fen).unwrap(), depth, check);
+    }
+}
+
+#[test]
+fn captures_and_quiets_split_all_moves() {
+    for_each_position(2, &mut |board| {
+        let captures = board.generate_moves(GenType::Captures);
+        let quiets = board.generate_moves(GenType::Quiets);
+        assert!(captures.iter().all(|m| m.is_capture() || m.is_promotion()));
+        assert!(quiets.iter().all(|m| !m.is_capture() && !m.is_promotion()));
+
+        let union: Vec<Move> = captures.iter().chain(&quiets).copied().collect();
+        assert_eq!(
+            sorted(&union),
+            sorted(&board.generate_pseudo_moves()),
+            "{:?}",
+            board
+        );
+    });
+}
+
+#[test]
+fn evasions_keep_every_legal_move_in_check() {
+    let mut positions = 0;
+    for_each_position(3, &mut |board| {
+        if !board.is_in_check(board.turn) {
+            return;
+        }
+        positions += 1;
+        let evasions = board.generate_moves(GenType::Evasions);
+        let all = sorted(&board.generate_pseudo_moves());
+        assert!(evasions.iter().all(|m| all.binary_search(&m.raw()).is_ok()));
+
+        let legal: Vec<Move> = evasions
+            .into_iter()
+            .filter(|&m| {
+                let mut after = *board;
+                after.apply_move(&m);
+                !after.is_in_check(board.turn)
+            })
+            .collect();
+        assert_eq!(
+            sorted(&legal),
+            sorted(&board.generate_legal_moves()),
+            "{:?}",
+            board
+        );
+    });
+    assert!(positions > 100);
+}
+
+#[test]
+fn quiet_checks_are_the_checking_quiets() {
+    for_each_position(2, &mut |board| {
+        // Compared on legal moves only: a king stepping next to the other king
+        // "attacks" it, but is never a check
+        let legal = board.generate_legal_moves();
+        let expected: Vec<Move> = legal
+            .iter()
+            .copied()
+            .filter(|m| m.is_quiet())
+            .filter(|&m| gives_check_by_playing(board, m))
+            .collect();
+        let quiet_checks: Vec<Move> = board
+            .generate_moves(GenType::QuietChecks)
+            .into_iter()
+            .filter(|m| legal.contains(m))
+            .collect();
+        assert_eq!(sorted(&quiet_checks), sorted(&expected), "{:?}", board);
+
+        for m in legal {
+            assert_eq!(
+                board.gives_check(m),
+                gives_check_by_playing(board, m),
+                "{} in {:?}",
+                m.to_uci(),
+                board
+            );
+        }
+    });
+}
+
+#[test]
+fn special_moves_that_give_check() {
+    let board = Board::from_fen("5k2/8/8/8/8/8/8/4K2R w K - 0 1").unwrap();
+    let castle = board.parse_uci_move("e1g1").unwrap();
+    assert!(board.gives_check(castle));
+    assert!(board.generate_moves(GenType::QuietChecks).contains(&castle));
+
+    // The bishop sees the king through b7 once the pawn has left it
+    let board = Board::from_fen("r7/1P6/8/3k4/8/8/8/4K3 w - - 0 1").unwrap();
+    assert!(board.gives_check(board.parse_uci_move("b7a8b").unwrap()));
+    assert!(!board.gives_check(board.parse_uci_move("b7a8r").unwrap()));
+    // Promotions are captures-stage moves, never quiet checks
+    assert!(!board.gives_check(board.parse_uci_move("b7b8r").unwrap()));
+    assert!(
+        board
+            .generate_moves(GenType::Captures)
+            .iter()
+            .any(|m| m.promotion() == Some(PieceType::Queen) && !m.is_capture())
+    );
+
+    let board = Board::from_fen("8/8/8/k2pP2R/8/8/8/4K3 w - d6 0 1").unwrap();
+    assert!(board.gives_check(board.parse_uci_move("e5d6").unwrap()));
+}
@@ -0,0 +1,85 @@
+use my_own_chess_engine::*;
+
+#[test]
+fn formats_coordinate_moves() {
+    let board = Board::from_fen("r3k2r/1P6/8/8/8/8/8/R3K2R w KQkq - 0 1").unwrap();
+    let find = |from: Square, to: Square, promotion: Option<PieceType>| {
+        board.encode_move(UnpackedMove {
+            from,
+            to,
+            promotion,
+        })
+    };
+
+    assert_eq!(find(A1, A2, None).to_uci(), "a1a2");
+    assert_eq!(find(B7, B8, Some(PieceType::Queen)).to_uci(), "b7b8q");
+    assert_eq!(find(B7, A8, Some(PieceType::Knight)).to_uci(), "b7a8n");
+    assert_eq!(find(E1, G1, None).to_uci(), "e1g1");
+    assert_eq!(
+        find(E1, G1, None).to_uci_with(CastlingNotation::KingTakesRook),
+        "e1h1"
+    );
+    assert_eq!(
+        find(E1, C1, None).to_uci_with(CastlingNotation::KingTakesRook),
+        "e1a1"
+    );
+    assert_eq!(
+        find(A1, A2, None).to_uci_with(CastlingNotation::KingTakesRook),
+        "a1a2"
+    );
+}
+
+#[test]
+fn parses_against_legal_moves() {
+    let board = Board::from_fen("r3k2r/1P6/8/8/8/8/8/R3K2R w KQkq - 0 1").unwrap();
+
+    let m = board.parse_uci_move("b7b8r").unwrap();
+    assert_eq!(
+        (m.from(), m.to(), m.promotion()),
+        (B7, B8, Some(PieceType::Rook))
+    );
+
+    let m = board.parse_uci_move("e1c1").unwrap();
+    assert_eq!((m.from(), m.to()), (E1, C1));
+
+    let m = board
+        .parse_uci_move_with("e1h1", CastlingNotation::KingTakesRook)
+        .unwrap();
+    assert_eq!((m.from(), m.to()), (E1, G1));
+    // In Chess960 form the two-square king move is not castling
+    assert!(
+        board
+            .parse_uci_move_with("e1g1", CastlingNotation::KingTakesRook)
+            .is_err()
+    );
+    // ...and in standard form the king cannot take its own rook
+    assert!(board.parse_uci_move("e1h1").is_err());
+
+    for m in board.generate_legal_moves() {
+        assert_eq!(board.parse_uci_move(&m.to_uci()), Ok(m));
+        let chess960 = m.to_uci_with(CastlingNotation::KingTakesRook);
+        assert_eq!(
+            board.parse_uci_move_with(&chess960, CastlingNotation::KingTakesRook),
+            Ok(m)
+        );
+    }
+}
+
+#[test]
+fn rejects_malformed_and_illegal_moves() {
+    let board = Board::new();
+    let malformed = |s: &str| Err(UciMoveError::Malformed(s.to_string()));
+    let illegal = |s: &str| Err(UciMoveError::Illegal(s.to_string()));
+
+    assert_eq!(board.parse_uci_move(""), malformed(""));
+    assert_eq!(board.parse_uci_move("e2"), malformed("e2"));
+    assert_eq!(board.parse_uci_move("e2e4qq"), malformed("e2e4qq"));
+    assert_eq!(board.parse_uci_move("E2E4"), malformed("E2E4"));
+    assert_eq!(board.parse_uci_move("e2e4k"), malformed("e2e4k"));
+    assert_eq!(board.parse_uci_move("e2e5"), illegal("e2e5"));
+    assert_eq!(board.parse_uci_move("e7e5"), illegal("e7e5"));
+    // A promotion letter on a non-promotion, or a missing one on a promotion
+    assert_eq!(board.parse_uci_move("e2e4q"), illegal("e2e4q"));
+    let board = Board::from_fen("4k3/1P6/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+    assert_eq!(board.parse_uci_move("b7b8"), illegal("b7b8"));
+}
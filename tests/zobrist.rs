@@ -0,0 +1,83 @@
+use my_own_chess_engine::*;
+
+fn play(mut board: Board, moves: &str) -> Board {
+    for uci in moves.split_whitespace() {
+        let m = board.parse_uci_move(uci).expect(uci);
+        board.apply_move(&m);
+        assert_eq!(board.hash, board.compute_hash(), "after {}", uci);
+    }
+    board
+}
+
+/// Check the incremental hash against a full recompute after every move
+fn walk(board: &Board, depth: u32) {
+    if depth == 0 {
+        return;
+    }
+    for m in board.generate_legal_moves() {
+        let mut next = *board;
+        next.apply_move(&m);
+        assert_eq!(next.hash, next.compute_hash(), "after {}", m.to_uci());
+        walk(&next, depth - 1);
+    }
+}
+
+#[test]
+fn incremental_hash_matches_recompute() {
+    for fen in [
+        // Captures, castling, and rook moves and captures that lose castling rights
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        // Promotions, with and without a capture
+        "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+        // En passant available, and double pushes that set it again
+        "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3",
+    ] {
+        walk(&Board::from_fen(fen).unwrap(), 3);
+    }
+}
+
+#[test]
+fn special_moves_keep_the_hash_in_step() {
+    let kiwipete =
+        Board::from_fen("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1")
+            .unwrap();
+    // Castling on both sides
+    let board = play(kiwipete, "e1g1 e8c8");
+    assert_eq!(board.castling_rights, 0);
+    // A rook leaving its corner, then a promotion capturing the other one
+    let board = play(kiwipete, "a1b1 h3g2 a2a3 g2h1q");
+    assert_eq!(board.castling_rights, BLACK_KINGSIDE | BLACK_QUEENSIDE);
+    // En passant capture
+    let board = play(
+        Board::from_fen("rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3").unwrap(),
+        "e5f6",
+    );
+    assert_eq!(board.en_passant, None);
+    // Promotion, then the new queen takes a rook that could still castle
+    let board = play(
+        Board::from_fen("r3k3/1P6/8/8/8/8/8/4K3 w q - 0 1").unwrap(),
+        "b7b8q e8e7 b8a8",
+    );
+    assert_eq!(board.castling_rights, 0);
+}
+
+#[test]
+fn transpositions_share_a_key() {
+    let start = Board::new();
+    let a = play(start, "g1f3 g8f6 b1c3 b8c6");
+    let b = play(start, "b1c3 b8c6 g1f3 g8f6");
+    assert_eq!(a.hash, b.hash);
+
+    // Knights out and back: the same position as at the start
+    let back = play(start, "g1f3 g8f6 f3g1 f6g8");
+    assert_eq!(back.hash, start.hash);
+}
+
+#[test]
+fn side_castling_rights_and_en_passant_change_the_key() {
+    let key = |fen: &str| Board::from_fen(fen).unwrap().hash;
+    let base = key("r3k2r/8/8/3pP3/8/8/8/R3K2R w KQkq - 0 1");
+    assert_ne!(base, key("r3k2r/8/8/3pP3/8/8/8/R3K2R b KQkq - 0 1"));
+    assert_ne!(base, key("r3k2r/8/8/3pP3/8/8/8/R3K2R w Kkq - 0 1"));
+    assert_ne!(base, key("r3k2r/8/8/3pP3/8/8/8/R3K2R w KQkq d6 0 1"));
+}